@@ -0,0 +1,90 @@
+//! compares the search based shanten and win detection with the lookup
+//! tables on random hands. run with `cargo bench`.
+
+use std::{hint::black_box, time::Instant};
+
+use mahjong_cli::{
+    decompose::decompose,
+    lookup,
+    player::Player,
+    rng::Rng,
+    ruleset::Ruleset,
+    shanten,
+    tile::{Tile, Wind, TILE_KINDS},
+};
+
+const HANDS: usize = 100_000;
+
+fn time<T>(name: &str, hands: &[T], f: impl Fn(&T) -> i64) -> f64 {
+    let start = Instant::now();
+    let mut total = 0;
+    for hand in hands {
+        total += f(black_box(hand));
+    }
+    let nanos = start.elapsed().as_nanos() as f64 / hands.len() as f64;
+    println!("{:<28} {:>10.1} ns/hand (checksum {})", name, nanos, total);
+    nanos
+}
+
+fn main() {
+    let mut rng = Rng::new(0);
+    let mut tiles = Ruleset::riichi().tiles();
+    let mut hands = vec![];
+    let mut players = vec![];
+    for _ in 0..HANDS {
+        rng.shuffle(&mut tiles);
+        hands.push(Tile::counts(&tiles[..14]));
+        players.push(Player::new(Wind::South, tiles[..14].to_vec()));
+    }
+
+    let start = Instant::now();
+    lookup::warm_up();
+    println!("building the tables: {:?}", start.elapsed());
+
+    let search = time("shanten (search)", &hands, |counts| {
+        shanten::shanten_of_counts(counts, 0) as i64
+    });
+    let table = time("shanten (lookup)", &hands, |counts| {
+        lookup::shanten(counts, 0) as i64
+    });
+    println!("speedup: {:.1}x", search / table);
+
+    let search = time("complete (decompose)", &hands, |counts| {
+        let tiles: Vec<Tile> = (0..TILE_KINDS)
+            .flat_map(|index| {
+                [Tile::from_index(index)].repeat(counts[index] as usize)
+            })
+            .collect();
+        decompose(&tiles).len().min(1) as i64
+    });
+    let table = time("complete (lookup)", &hands, |counts| {
+        lookup::is_complete(counts, 0) as i64
+    });
+    println!("speedup: {:.1}x", search / table);
+
+    // the engine's own checks, which go through the tables.
+    let search = time("Player shanten (search)", &players, |player| {
+        shanten::shanten(&player.hand, player.melds()) as i64
+    });
+    let table = time("Player::shanten", &players, |player| {
+        player.shanten() as i64
+    });
+    println!("speedup: {:.1}x", search / table);
+    time("Player::is_complete", &players, |player| {
+        player.is_complete() as i64
+    });
+
+    // the 13 tiles left after throwing the last one, read for their waits.
+    let waiting: Vec<Player> = players
+        .iter()
+        .take(HANDS / 10)
+        .map(|player| Player::new(player.seat, player.hand[..13].to_vec()))
+        .collect();
+    time("Player::waits", &waiting, |player| {
+        player.waits().len() as i64
+    });
+
+    time("evaluate_yakus", &players, |player| {
+        player.evaluate_yakus(Wind::East).len() as i64
+    });
+}
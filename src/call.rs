@@ -0,0 +1,342 @@
+use std::fmt::Display;
+
+use crate::{
+    decompose::Set,
+    efficiency::{acceptance, accepted_tiles, estimate_value},
+    player::Player,
+    potential::{potential, Reach},
+    ruleset::Kuikae,
+    tile::{Tile, Wind, TILE_KINDS},
+    yaku::Yaku,
+};
+
+/// a call on another player's discard.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Call {
+    /// a sequence, starting with this tile, only on the discard of the
+    /// player to the left.
+    Chi(Tile),
+    Pon,
+    /// an open quad from three concealed copies.
+    Kan,
+}
+
+impl Display for Call {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Call::Chi(start) => {
+                write!(f, "chi")?;
+                for tile in Set::Sequence(*start).tiles() {
+                    write!(f, " {}", tile)?;
+                }
+                Ok(())
+            }
+            Call::Pon => write!(f, "pon"),
+            Call::Kan => write!(f, "kan"),
+        }
+    }
+}
+
+impl Call {
+    /// every call the player's hand allows on `tile`, chi only when
+    /// `can_chi`.
+    pub fn available(player: &Player, tile: Tile, can_chi: bool) -> Vec<Call> {
+        let mut calls = vec![];
+        let Some(index) = tile.index() else {
+            return calls;
+        };
+        let counts = Tile::counts(&player.hand);
+
+        if can_chi && index < 27 {
+            let number = index % 9;
+            for start in number.saturating_sub(2)..=number.min(6) {
+                let start = index - number + start;
+                if (start..start + 3)
+                    .all(|other| other == index || counts[other] > 0)
+                {
+                    calls.push(Call::Chi(Tile::from_index(start)));
+                }
+            }
+        }
+        if counts[index] >= 2 {
+            calls.push(Call::Pon);
+        }
+        if counts[index] >= 3 {
+            calls.push(Call::Kan);
+        }
+        calls
+    }
+
+    /// moves the tiles of the call on `tile` from the player's hand into
+    /// their melds, false without touching the hand when it lacks them.
+    pub fn apply(self, player: &mut Player, tile: Tile) -> bool {
+        let (from_hand, set): (Vec<Tile>, Vec<Tile>) = match self {
+            Call::Chi(start) => {
+                let set = Set::Sequence(start).tiles().to_vec();
+                let mut from_hand = set.clone();
+                let Some(position) = from_hand.iter().position(|t| *t == tile)
+                else {
+                    return false;
+                };
+                from_hand.remove(position);
+                (from_hand, set)
+            }
+            Call::Pon => (vec![tile; 2], vec![tile; 3]),
+            Call::Kan => (vec![tile; 3], vec![tile; 3]),
+        };
+
+        let mut hand = player.hand.clone();
+        for needed in &from_hand {
+            match hand.iter().position(|t| t == needed) {
+                Some(position) => {
+                    hand.remove(position);
+                }
+                None => return false,
+            }
+        }
+
+        player.hand = hand;
+        player.dealt_in.extend(set);
+        if self == Call::Kan {
+            player.kan.push(tile);
+        }
+        true
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CallAdvice {
+    pub call: Call,
+    pub shanten_before: i8,
+    pub acceptance_before: u32,
+    /// after the call and the best discard following it, or after the
+    /// replacement draw of a kan.
+    pub shanten_after: i8,
+    pub acceptance_after: u32,
+    /// the discard to make after the call, none for a kan.
+    pub discard: Option<Tile>,
+    /// the fastest yaku the open hand can still win with, none when every
+    /// one would take longer than the hand itself.
+    pub yaku: Option<Yaku>,
+    /// the estimated han of the hand with its current yakus and dora,
+    /// closed before the call and open after it.
+    pub value_before: u8,
+    pub value_after: u8,
+    pub recommended: bool,
+    pub explanation: String,
+}
+
+/// weighs every call the player could make on `tile`: the speed it gains,
+/// whether the open hand keeps a yaku and the han it costs. the discard
+/// after a chi or pon is never one `kuikae` forbids.
+pub fn advise_calls(
+    player: &Player,
+    tile: Tile,
+    can_chi: bool,
+    unseen: &[u8; TILE_KINDS],
+    doras: &[Tile],
+    prevalent_wind: Wind,
+    kuikae: Kuikae,
+) -> Vec<CallAdvice> {
+    let menzenchin = player.is_menzenchin();
+    let count = |tiles: &[(Tile, u8)]| {
+        tiles.iter().map(|(_, count)| *count as u32).sum::<u32>()
+    };
+    let (shanten_before, tiles_before) =
+        accepted_tiles(&Tile::counts(&player.hand), player.melds(), unseen);
+    let value_before =
+        estimate_value(player, doras, prevalent_wind).han(menzenchin);
+
+    let mut advice = vec![];
+    for call in Call::available(player, tile, can_chi) {
+        let mut after = player.clone();
+        if !call.apply(&mut after, tile) {
+            continue;
+        }
+
+        let (shanten_after, acceptance_after, discard) = if call == Call::Kan {
+            let (shanten, tiles) = accepted_tiles(
+                &Tile::counts(&after.hand),
+                after.melds(),
+                unseen,
+            );
+            (shanten, count(&tiles), None)
+        } else {
+            let forbidden = kuikae.forbidden(call, tile);
+            match acceptance(&after.hand, after.melds(), unseen)
+                .into_iter()
+                .find(|best| !forbidden.contains(&best.discard))
+            {
+                Some(best) => {
+                    let position =
+                        after.hand.iter().position(|t| *t == best.discard);
+                    if let Some(position) = position {
+                        after.hand.remove(position);
+                    }
+                    (best.shanten, best.count(), Some(best.discard))
+                }
+                None => continue,
+            }
+        };
+
+        // a yaku that finishes no later than the hand does.
+        let pace = (shanten_after + 1).max(0) as u8;
+        let yaku = potential(&after, prevalent_wind)
+            .into_iter()
+            .filter(|potential| potential.han > 0)
+            .find_map(|potential| match potential.reach {
+                Reach::Tiles(tiles) if tiles <= pace => Some(potential.yaku),
+                _ => None,
+            });
+        let value_after =
+            estimate_value(&after, doras, prevalent_wind).han(false);
+
+        let faster = shanten_after < shanten_before;
+        let cost = value_before.saturating_sub(value_after);
+        let recommended = faster && yaku.is_some() && cost <= 2;
+        let explanation = match (faster, yaku) {
+            (false, _) => format!(
+                "{} doesn't lower the shanten from {}",
+                call, shanten_before
+            ),
+            (true, None) => {
+                format!("{} leaves the open hand without a yaku in time", call)
+            }
+            (true, Some(yaku)) => format!(
+                "{} goes to {} shanten with {} tiles, keeping {}, {} han lost",
+                call, shanten_after, acceptance_after, yaku, cost
+            ),
+        };
+
+        advice.push(CallAdvice {
+            call,
+            shanten_before,
+            acceptance_before: count(&tiles_before),
+            shanten_after,
+            acceptance_after,
+            discard,
+            yaku,
+            value_before,
+            value_after,
+            recommended,
+            explanation,
+        });
+    }
+
+    advice.sort_by(|first, second| {
+        second
+            .recommended
+            .cmp(&first.recommended)
+            .then(first.shanten_after.cmp(&second.shanten_after))
+            .then(second.acceptance_after.cmp(&first.acceptance_after))
+    });
+    advice
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        call::{advise_calls, Call},
+        efficiency::unseen,
+        player::Player,
+        ruleset::Kuikae,
+        tile::{tiles, Dragon, Wind},
+        yaku::Yaku,
+    };
+
+    #[test]
+    fn available_calls() {
+        let player = Player::new(
+            Wind::South,
+            tiles("M2 M3 M4 M5 P5 P5 P5 S1 S9 EW EW NW NW"),
+        );
+        let m3 = tiles("M3")[0];
+        assert_eq!(
+            Call::available(&player, m3, true),
+            vec![Call::Chi(tiles("M2")[0]), Call::Chi(tiles("M3")[0]),]
+        );
+        assert!(Call::available(&player, m3, false).is_empty());
+        let p5 = tiles("P5")[0];
+        assert_eq!(
+            Call::available(&player, p5, true),
+            vec![Call::Pon, Call::Kan]
+        );
+
+        let mut called = player.clone();
+        assert!(Call::Chi(m3).apply(&mut called, m3));
+        assert_eq!(called.hand.len(), 11);
+        assert_eq!(called.dealt_in, tiles("M3 M4 M5"));
+        assert!(!Call::Chi(tiles("S1")[0]).apply(&mut called, m3));
+    }
+
+    #[test]
+    fn advice() {
+        // a pon of a value tile speeds the hand up and keeps a yaku.
+        let player = Player::new(
+            Wind::South,
+            tiles("M2 M3 M4 P6 P7 S3 S4 S9 RD RD EW NW WD"),
+        );
+        let rd = tiles("RD")[0];
+        let advice = advise_calls(
+            &player,
+            rd,
+            false,
+            &unseen(&player.hand),
+            &[],
+            Wind::East,
+            Kuikae::Strict,
+        );
+        assert_eq!(advice.len(), 1);
+        assert_eq!(advice[0].call, Call::Pon);
+        assert!(advice[0].shanten_after < advice[0].shanten_before);
+        assert_eq!(advice[0].yaku, Some(Yaku::Dragons(Dragon::Red)));
+        assert!(advice[0].recommended);
+
+        // a chi that leaves nothing but terminals to win with.
+        let player = Player::new(
+            Wind::South,
+            tiles("M2 M3 M4 P6 P7 S3 S4 S9 M9 M9 M1 S1 P1"),
+        );
+        let p8 = tiles("P8")[0];
+        let advice = advise_calls(
+            &player,
+            p8,
+            true,
+            &unseen(&player.hand),
+            &[],
+            Wind::East,
+            Kuikae::Strict,
+        );
+        assert_eq!(advice[0].call, Call::Chi(tiles("P6")[0]));
+        assert!(advice[0].shanten_after < advice[0].shanten_before);
+        assert_eq!(advice[0].yaku, None);
+        assert!(!advice[0].recommended);
+    }
+
+    #[test]
+    fn kuikae() {
+        // the fourth red dragon is the best discard after the pon, when the
+        // rules allow throwing it.
+        let player = Player::new(
+            Wind::South,
+            tiles("RD RD RD M2 M3 M4 P2 P3 P4 S2 S3 S4 NW"),
+        );
+        let rd = tiles("RD")[0];
+        let discard = |kuikae| {
+            advise_calls(
+                &player,
+                rd,
+                false,
+                &unseen(&player.hand),
+                &[],
+                Wind::East,
+                kuikae,
+            )
+            .into_iter()
+            .find(|advice| advice.call == Call::Pon)
+            .and_then(|advice| advice.discard)
+        };
+        assert_eq!(discard(Kuikae::Allowed), Some(rd));
+        assert_eq!(discard(Kuikae::Strict), Some(tiles("NW")[0]));
+    }
+}
@@ -0,0 +1,132 @@
+use crate::tile::{Tile, TILE_KINDS};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Set {
+    /// three consecutive tiles, starting with this one.
+    Sequence(Tile),
+    Triplet(Tile),
+}
+
+/// one way of reading a complete hand as sets and a pair.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Decomposition {
+    pub pair: Tile,
+    pub sets: Vec<Set>,
+}
+
+impl Set {
+    pub fn contains(self, tile: Tile) -> bool {
+        self.tiles().contains(&tile)
+    }
+
+    pub fn tiles(self) -> [Tile; 3] {
+        match self {
+            Set::Triplet(tile) => [tile; 3],
+            Set::Sequence(tile) => {
+                let index = tile.index().unwrap_or_default();
+                [
+                    tile,
+                    Tile::from_index(index + 1),
+                    Tile::from_index(index + 2),
+                ]
+            }
+        }
+    }
+}
+
+/// every way the concealed `tiles` split into a pair and sets, none when
+/// they don't form a standard complete hand. called sets aren't part of
+/// `tiles`, so a hand with melds has fewer sets.
+pub fn decompose(tiles: &[Tile]) -> Vec<Decomposition> {
+    let mut decompositions = vec![];
+    if tiles.len() % 3 != 2 {
+        return decompositions;
+    }
+
+    let mut counts = Tile::counts(tiles);
+    for pair in 0..TILE_KINDS {
+        if counts[pair] < 2 {
+            continue;
+        }
+        counts[pair] -= 2;
+        let mut sets = vec![];
+        search(&mut counts, 0, &mut sets, &mut |sets| {
+            decompositions.push(Decomposition {
+                pair: Tile::from_index(pair),
+                sets: sets.to_vec(),
+            })
+        });
+        counts[pair] += 2;
+    }
+    decompositions
+}
+
+fn search(
+    counts: &mut [u8; TILE_KINDS],
+    mut index: usize,
+    sets: &mut Vec<Set>,
+    found: &mut dyn FnMut(&[Set]),
+) {
+    while index < TILE_KINDS && counts[index] == 0 {
+        index += 1;
+    }
+    if index == TILE_KINDS {
+        found(sets);
+        return;
+    }
+
+    // the lowest remaining tile has to start a triplet or a sequence.
+    if counts[index] >= 3 {
+        counts[index] -= 3;
+        sets.push(Set::Triplet(Tile::from_index(index)));
+        search(counts, index, sets, found);
+        sets.pop();
+        counts[index] += 3;
+    }
+    if index < 27
+        && index % 9 < 7
+        && counts[index + 1] > 0
+        && counts[index + 2] > 0
+    {
+        for offset in 0..3 {
+            counts[index + offset] -= 1;
+        }
+        sets.push(Set::Sequence(Tile::from_index(index)));
+        search(counts, index, sets, found);
+        sets.pop();
+        for offset in 0..3 {
+            counts[index + offset] += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        decompose::{decompose, Set},
+        tile::{tiles, Simple, Tile},
+    };
+
+    #[test]
+    fn decompositions() {
+        // three identical sequences are also three triplets.
+        let hand = tiles("M1 M1 M1 M2 M2 M2 M3 M3 M3 P5 P6 P7 NW NW");
+        let decompositions = decompose(&hand);
+        assert_eq!(decompositions.len(), 2);
+        assert!(decompositions
+            .iter()
+            .all(|d| d.pair == "NW".parse().unwrap()));
+        assert!(decompositions.iter().any(|d| d
+            .sets
+            .contains(&Set::Sequence(Tile::Simple(Simple::Man(1))))));
+
+        // 11123 only reads as 11 + 123.
+        let hand = tiles("M1 M1 M1 M2 M3");
+        assert_eq!(decompose(&hand).len(), 1);
+        let hand = tiles("M1 M1 M1 M2 M4");
+        assert!(decompose(&hand).is_empty());
+
+        assert!(decompose(&tiles("M8 M9 P1 P1 P1")).is_empty());
+        assert!(decompose(&tiles("EW SW WW GD GD")).is_empty());
+    }
+}
@@ -0,0 +1,294 @@
+use std::fmt::Display;
+
+use crate::{
+    player::Player,
+    tile::{Tile, TILE_KINDS},
+};
+
+/// why a tile is as safe or as dangerous as it is against an opponent.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Reason {
+    /// the opponent discarded it themselves, or let it pass in riichi, and
+    /// can't win on it.
+    Genbutsu,
+    /// an honor with this many copies in sight, leaving only a tanki or a
+    /// shanpon on the rest.
+    Honor(u8),
+    /// every two-sided wait on it would be furiten for the opponent.
+    Suji,
+    /// only one side of a middle tile is covered by suji.
+    HalfSuji,
+    /// the tiles a two-sided wait on it needs are all in sight.
+    NoChance,
+    /// a single copy of a tile a two-sided wait needs is left.
+    OneChance,
+    /// nothing speaks for the tile.
+    Live,
+}
+
+impl Display for Reason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reason::Genbutsu => write!(f, "genbutsu"),
+            Reason::Honor(visible) => write!(f, "honor, {} visible", visible),
+            Reason::Suji => write!(f, "suji"),
+            Reason::HalfSuji => write!(f, "half suji"),
+            Reason::NoChance => write!(f, "no chance"),
+            Reason::OneChance => write!(f, "one chance"),
+            Reason::Live => write!(f, "live"),
+        }
+    }
+}
+
+/// how likely discarding a tile deals into the most threatening opponent.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Danger {
+    pub tile: Tile,
+    /// a rough chance in percent of dealing in, weighted by how close the
+    /// opponent is to winning.
+    pub danger: f32,
+    /// the opponent the danger comes from, an index into `opponents`.
+    pub against: usize,
+    pub reasons: Vec<Reason>,
+}
+
+/// rates every distinct tile in the player's hand against the opponents,
+/// safest first. `visible` holds every tile the player can see, see
+/// `Player::visible_tiles`.
+pub fn assess_danger(
+    player: &Player,
+    opponents: &[Player],
+    visible: &[Tile],
+) -> Vec<Danger> {
+    let visible = Tile::counts(visible);
+    let mut dangers: Vec<Danger> = vec![];
+
+    for tile in &player.hand {
+        if dangers.iter().any(|danger| danger.tile == *tile) {
+            continue;
+        }
+        let Some(index) = tile.index() else {
+            continue;
+        };
+
+        let mut worst = Danger {
+            tile: *tile,
+            danger: 0.0,
+            against: 0,
+            reasons: vec![Reason::Genbutsu],
+        };
+        for (against, opponent) in opponents.iter().enumerate() {
+            let (danger, reasons) = rate(index, opponent, &visible);
+            let danger = danger * threat(opponent);
+            if against == 0 || danger > worst.danger {
+                worst = Danger {
+                    tile: *tile,
+                    danger,
+                    against,
+                    reasons,
+                };
+            }
+        }
+        dangers.push(worst);
+    }
+
+    dangers.sort_by(|first, second| first.danger.total_cmp(&second.danger));
+    dangers
+}
+
+/// how close an opponent is likely to be to winning, from 1 for a riichi
+/// down to a quiet closed hand.
+fn threat(opponent: &Player) -> f32 {
+    if opponent.riichi {
+        return 1.0;
+    }
+    match opponent.melds() {
+        0 => 0.15,
+        1 => 0.25,
+        2 => 0.5,
+        _ => 0.75,
+    }
+}
+
+/// the chance in percent of the tile at `index` dealing into a tenpai
+/// opponent, roughly following common deal-in statistics against riichi.
+fn rate(
+    index: usize,
+    opponent: &Player,
+    visible: &[u8; TILE_KINDS],
+) -> (f32, Vec<Reason>) {
+    // tiles called away from the river and passed in riichi are as safe as
+    // the ones still lying there.
+    let discarded =
+        Tile::counts(&[opponent.river(), opponent.passed.clone()].concat());
+    if discarded[index] > 0 {
+        return (0.0, vec![Reason::Genbutsu]);
+    }
+
+    if index >= 27 {
+        let danger = match visible[index] {
+            0 => 8.0,
+            1 => 6.0,
+            2 => 3.0,
+            3 => 1.0,
+            _ => 0.0,
+        };
+        return (danger, vec![Reason::Honor(visible[index])]);
+    }
+
+    let suit = index / 9 * 9;
+    let number = index - suit;
+
+    // the two-sided waits on the tile: one with the two tiles above it
+    // waiting on it and three higher, one with the two below.
+    let mut sides = vec![];
+    if number <= 5 {
+        sides.push((index + 3, [index + 1, index + 2]));
+    }
+    if number >= 3 {
+        sides.push((index - 3, [index - 2, index - 1]));
+    }
+
+    let mut reasons = vec![];
+    let mut live = 0;
+    let mut one_chance = true;
+    for (suji, shape) in &sides {
+        if discarded[*suji] > 0 {
+            continue;
+        }
+        let fewest = shape
+            .iter()
+            .map(|other| 4 - visible[*other].min(4))
+            .min()
+            .unwrap_or(0);
+        match fewest {
+            0 => {}
+            1 => live += 1,
+            _ => {
+                live += 1;
+                one_chance = false;
+            }
+        }
+    }
+
+    let edge = number.min(8 - number);
+    let suji_covered = sides
+        .iter()
+        .filter(|(suji, _)| discarded[*suji] > 0)
+        .count();
+    let danger = if live == 0 {
+        // only a kanchan, penchan, shanpon or tanki is left.
+        reasons.push(if suji_covered == sides.len() {
+            Reason::Suji
+        } else {
+            Reason::NoChance
+        });
+        [3.0, 4.0, 5.0, 5.0, 5.0][edge]
+    } else {
+        if suji_covered > 0 {
+            reasons.push(Reason::HalfSuji);
+        }
+        let mut danger = if suji_covered > 0 {
+            8.0
+        } else {
+            [6.0, 8.0, 10.0, 12.0, 12.0][edge]
+        };
+        if one_chance {
+            reasons.push(Reason::OneChance);
+            danger *= 0.7;
+        }
+        if reasons.is_empty() {
+            reasons.push(Reason::Live);
+        }
+        danger
+    };
+    (danger, reasons)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        defense::{assess_danger, Reason},
+        player::Player,
+        tile::{tiles, Wind},
+    };
+
+    fn player(seat: Wind, hand: &str, discarded: &str) -> Player {
+        Player {
+            discarded: tiles(discarded),
+            ..Player::new(seat, tiles(hand))
+        }
+    }
+
+    #[test]
+    fn ratings() {
+        let me = player(
+            Wind::East,
+            "M1 M5 M7 M9 P3 P6 S4 S8 S8 S8 S8 S9 WD NW",
+            "WD WD",
+        );
+        let mut riichi = player(Wind::South, "", "M4 P3 P9 S1 S5 EW");
+        riichi.riichi = true;
+        let quiet = player(Wind::West, "", "M5 M7 M7");
+        let opponents = [riichi, quiet];
+
+        let dangers =
+            assess_danger(&me, &opponents, &me.visible_tiles(&opponents, &[]));
+        let danger = |tile: &str| {
+            let tile = tiles(tile)[0];
+            dangers
+                .iter()
+                .find(|danger| danger.tile == tile)
+                .unwrap()
+                .clone()
+        };
+
+        assert_eq!(danger("M1").reasons, vec![Reason::Suji]);
+        assert_eq!(danger("M7").reasons, vec![Reason::Suji]);
+        assert_eq!(danger("P6").reasons, vec![Reason::Suji]);
+        assert_eq!(danger("S4").reasons, vec![Reason::HalfSuji]);
+        // all four 8 sou are in the hand.
+        assert_eq!(danger("S9").reasons, vec![Reason::NoChance]);
+        // three 7 man are in sight.
+        assert_eq!(danger("M9").reasons, vec![Reason::OneChance]);
+        assert_eq!(danger("WD").reasons, vec![Reason::Honor(3)]);
+        assert_eq!(danger("M5").reasons, vec![Reason::Live]);
+        assert_eq!(danger("M5").against, 0);
+
+        // genbutsu against the riichi, so the danger comes from the other
+        // player, who is far from winning.
+        let p3 = danger("P3");
+        assert_eq!(p3.against, 1);
+        assert!(p3.danger < danger("M1").danger);
+
+        assert_eq!(dangers[0].tile, tiles("WD")[0]);
+        assert_eq!(dangers.last().unwrap().tile, tiles("M5")[0]);
+    }
+
+    #[test]
+    fn called_and_passed() {
+        let me = player(Wind::East, "M5 P5 S2 S5", "");
+        let mut riichi = player(Wind::South, "", "EW");
+        riichi.riichi = true;
+        // a 5 man someone called, and a 5 pin and a 2 sou the riichi let go
+        // by.
+        riichi.called = tiles("M5");
+        riichi.passed = tiles("P5 S2");
+        let opponents = [riichi];
+
+        let dangers =
+            assess_danger(&me, &opponents, &me.visible_tiles(&opponents, &[]));
+        let reasons = |tile: &str| {
+            let tile = tiles(tile)[0];
+            dangers
+                .iter()
+                .find(|danger| danger.tile == tile)
+                .unwrap()
+                .reasons
+                .clone()
+        };
+        assert_eq!(reasons("M5"), vec![Reason::Genbutsu]);
+        assert_eq!(reasons("P5"), vec![Reason::Genbutsu]);
+        assert_eq!(reasons("S5"), vec![Reason::HalfSuji]);
+    }
+}
@@ -0,0 +1,416 @@
+use crate::{
+    lookup,
+    player::Player,
+    shanten::{seven_pairs_shanten, standard_shanten},
+    tile::{Dragon, Tile, Wind, TILE_KINDS},
+    yaku::Yaku,
+};
+
+/// how good a discard is for speed: the shanten it leaves and the tiles
+/// that would then bring the hand closer to tenpai.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Acceptance {
+    pub discard: Tile,
+    pub shanten: i8,
+    /// every tile lowering the shanten, with how many copies are unseen.
+    pub tiles: Vec<(Tile, u8)>,
+}
+
+impl Acceptance {
+    /// the number of unseen tiles that improve the hand, the ukeire.
+    pub fn count(&self) -> u32 {
+        self.tiles.iter().map(|(_, count)| *count as u32).sum()
+    }
+}
+
+/// copies of each tile the player can't see, 4 minus those in `visible`.
+pub fn unseen(visible: &[Tile]) -> [u8; TILE_KINDS] {
+    let mut unseen = [4u8; TILE_KINDS];
+    for (index, count) in Tile::counts(visible).iter().enumerate() {
+        unseen[index] = unseen[index].saturating_sub(*count);
+    }
+    unseen
+}
+
+/// the shanten of a hand waiting for a draw (13 tiles minus melds) and the
+/// tiles that would lower it.
+pub fn accepted_tiles(
+    counts: &[u8; TILE_KINDS],
+    melds: usize,
+    unseen: &[u8; TILE_KINDS],
+) -> (i8, Vec<(Tile, u8)>) {
+    let mut counts = *counts;
+    let shanten = lookup::shanten(&counts, melds);
+    let mut tiles = vec![];
+    for index in 0..TILE_KINDS {
+        if counts[index] >= 4 {
+            continue;
+        }
+        counts[index] += 1;
+        if lookup::shanten(&counts, melds) < shanten {
+            tiles.push((Tile::from_index(index), unseen[index]));
+        }
+        counts[index] -= 1;
+    }
+    (shanten, tiles)
+}
+
+/// the acceptance of every distinct discard from a hand that just drew
+/// (14 tiles minus melds), best first: lowest shanten, then most tiles.
+pub fn acceptance(
+    tiles: &[Tile],
+    melds: usize,
+    unseen: &[u8; TILE_KINDS],
+) -> Vec<Acceptance> {
+    let mut counts = Tile::counts(tiles);
+    let mut acceptances = vec![];
+    for index in 0..TILE_KINDS {
+        if counts[index] == 0 {
+            continue;
+        }
+        counts[index] -= 1;
+        let (shanten, tiles) = accepted_tiles(&counts, melds, unseen);
+        acceptances.push(Acceptance {
+            discard: Tile::from_index(index),
+            shanten,
+            tiles,
+        });
+        counts[index] += 1;
+    }
+    acceptances.sort_by(|first, second| {
+        first
+            .shanten
+            .cmp(&second.shanten)
+            .then(second.count().cmp(&first.count()))
+    });
+    acceptances
+}
+
+/// the yakus a hand is heading for and its dora, a rough idea of what it
+/// will be worth.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct HandValue {
+    pub yakus: Vec<Yaku>,
+    pub dora: u8,
+}
+
+impl HandValue {
+    pub fn han(&self, menzenchin: bool) -> u8 {
+        self.yakus
+            .iter()
+            .map(|yaku| yaku.han(menzenchin))
+            .sum::<u8>()
+            + self.dora
+    }
+}
+
+/// estimates what the player's current hand is worth if it wins without
+/// changing direction: dora, riichi for closed hands, value triplets, all
+/// simples, flushes and seven pairs.
+pub fn estimate_value(
+    player: &Player,
+    doras: &[Tile],
+    prevalent_wind: Wind,
+) -> HandValue {
+    let tiles = [player.hand.clone(), player.meld_tiles()].concat();
+    let counts = Tile::counts(&tiles);
+    let menzenchin = player.is_menzenchin();
+    let mut yakus = vec![];
+
+    if menzenchin {
+        yakus.push(Yaku::Riichi);
+    }
+
+    if tiles.iter().all(|tile| !tile.is_terminal_or_honor()) {
+        yakus.push(Yaku::AllSimples);
+    }
+
+    for dragon in [Dragon::Green, Dragon::Red, Dragon::White] {
+        if counts[Tile::Dragon(dragon).index().unwrap_or_default()] >= 3 {
+            yakus.push(Yaku::Dragons(dragon));
+        }
+    }
+    for wind in [Wind::East, Wind::South, Wind::West, Wind::North] {
+        if counts[Tile::Wind(wind).index().unwrap_or_default()] >= 3 {
+            if wind == player.seat {
+                yakus.push(Yaku::SeatWind(wind));
+            }
+            if wind == prevalent_wind {
+                yakus.push(Yaku::PrevalentWind(wind));
+            }
+        }
+    }
+
+    let suits: Vec<usize> = (0..3)
+        .filter(|suit| counts[suit * 9..suit * 9 + 9].iter().any(|c| *c > 0))
+        .collect();
+    let honors = counts[27..].iter().any(|count| *count > 0);
+    if suits.len() == 1 {
+        yakus.push(if honors {
+            Yaku::HalfFlush
+        } else {
+            Yaku::FullFlush
+        });
+    }
+
+    let concealed = Tile::counts(&player.hand);
+    if menzenchin
+        && seven_pairs_shanten(&concealed) < standard_shanten(&concealed, 0)
+    {
+        yakus.push(Yaku::SevenPairs);
+    }
+
+    let dora = tiles
+        .iter()
+        .map(|tile| doras.iter().filter(|dora| *dora == tile).count() as u8)
+        .sum();
+
+    HandValue { yakus, dora }
+}
+
+/// a ranked discard with what it was ranked on.
+#[derive(Debug, Clone)]
+pub struct DiscardCandidate {
+    pub acceptance: Acceptance,
+    /// the average acceptance after drawing one of the accepted tiles and
+    /// making the best discard again, weighted by how many are unseen. it
+    /// tells good shapes from ones that only advance into bad waits.
+    pub second_step: f32,
+    pub value: HandValue,
+    pub explanation: String,
+}
+
+/// ranks every discard from the player's hand after a draw by the shanten
+/// it leaves, its acceptance, the acceptance one step further and the
+/// estimated value of the hand, best first.
+pub fn recommend_discards(
+    player: &Player,
+    unseen: &[u8; TILE_KINDS],
+    doras: &[Tile],
+    prevalent_wind: Wind,
+) -> Vec<DiscardCandidate> {
+    let melds = player.melds();
+    let menzenchin = player.is_menzenchin();
+
+    let mut candidates: Vec<DiscardCandidate> =
+        acceptance(&player.hand, melds, unseen)
+            .into_iter()
+            .map(|acceptance| {
+                let mut after = player.clone();
+                if let Some(position) =
+                    after.hand.iter().position(|t| *t == acceptance.discard)
+                {
+                    after.hand.remove(position);
+                }
+
+                let second_step = if acceptance.shanten > 0 {
+                    second_step(&after.hand, melds, &acceptance, unseen)
+                } else {
+                    0.0
+                };
+                let value = estimate_value(&after, doras, prevalent_wind);
+                let explanation =
+                    explain(&acceptance, second_step, &value, menzenchin);
+
+                DiscardCandidate {
+                    acceptance,
+                    second_step,
+                    value,
+                    explanation,
+                }
+            })
+            .collect();
+
+    candidates.sort_by(|first, second| {
+        first
+            .acceptance
+            .shanten
+            .cmp(&second.acceptance.shanten)
+            .then(second.acceptance.count().cmp(&first.acceptance.count()))
+            .then(second.second_step.total_cmp(&first.second_step))
+            .then(
+                second
+                    .value
+                    .han(menzenchin)
+                    .cmp(&first.value.han(menzenchin)),
+            )
+    });
+    candidates
+}
+
+fn second_step(
+    tiles: &[Tile],
+    melds: usize,
+    acceptance: &Acceptance,
+    unseen: &[u8; TILE_KINDS],
+) -> f32 {
+    let mut counts = Tile::counts(tiles);
+    let mut unseen = *unseen;
+    let mut total = 0;
+    let mut weighted = 0;
+
+    for (tile, copies) in &acceptance.tiles {
+        let drawn = tile.index().unwrap_or_default();
+        if *copies == 0 {
+            continue;
+        }
+        counts[drawn] += 1;
+        unseen[drawn] -= 1;
+
+        let mut best = 0;
+        for discard in 0..TILE_KINDS {
+            if counts[discard] == 0 {
+                continue;
+            }
+            counts[discard] -= 1;
+            let (shanten, tiles) = accepted_tiles(&counts, melds, &unseen);
+            if shanten < acceptance.shanten {
+                best = best.max(
+                    tiles.iter().map(|(_, count)| *count as u32).sum::<u32>(),
+                );
+            }
+            counts[discard] += 1;
+        }
+
+        counts[drawn] -= 1;
+        unseen[drawn] += 1;
+        total += *copies as u32;
+        weighted += *copies as u32 * best;
+    }
+
+    if total == 0 {
+        0.0
+    } else {
+        weighted as f32 / total as f32
+    }
+}
+
+fn explain(
+    acceptance: &Acceptance,
+    second_step: f32,
+    value: &HandValue,
+    menzenchin: bool,
+) -> String {
+    let mut explanation = match acceptance.shanten {
+        -1 => "complete hand".to_string(),
+        0 => format!("tenpai on {} tiles", acceptance.count()),
+        shanten => format!(
+            "{} shanten, {} tiles improve, {:.1} on average after that",
+            shanten,
+            acceptance.count(),
+            second_step
+        ),
+    };
+
+    if !acceptance.tiles.is_empty() {
+        explanation.push_str(" (");
+        for (index, (tile, count)) in acceptance.tiles.iter().enumerate() {
+            if index > 0 {
+                explanation.push(' ');
+            }
+            explanation.push_str(&format!("{}x{}", tile, count));
+        }
+        explanation.push(')');
+    }
+
+    explanation.push_str(&format!(", about {} han", value.han(menzenchin)));
+    if !value.yakus.is_empty() || value.dora > 0 {
+        explanation.push_str(": ");
+        let mut parts: Vec<String> =
+            value.yakus.iter().map(|yaku| yaku.to_string()).collect();
+        if value.dora > 0 {
+            parts.push(format!("dora {}", value.dora));
+        }
+        explanation.push_str(&parts.join(", "));
+    }
+    explanation
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        efficiency::{acceptance, recommend_discards, unseen},
+        player::Player,
+        tile::{tiles, Tile, Wind},
+        yaku::Yaku,
+    };
+
+    #[test]
+    fn discards() {
+        let hand = tiles("M1 M2 M3 P4 P5 P6 S7 S8 S9 EW EW P2 P3 NW");
+        let acceptances = acceptance(&hand, 0, &unseen(&hand));
+
+        let best = &acceptances[0];
+        assert_eq!(best.discard.to_string(), "NW");
+        assert_eq!(best.shanten, 0);
+        // 23456 pin waits on 1, 4 and 7.
+        assert_eq!(best.count(), 11);
+        assert_eq!(
+            best.tiles,
+            vec![
+                ("P1".parse().unwrap(), 4),
+                ("P4".parse().unwrap(), 3),
+                ("P7".parse().unwrap(), 4),
+            ]
+        );
+
+        // every other discard goes back to 1 shanten.
+        assert!(acceptances[1..].iter().all(|other| other.shanten == 1));
+    }
+
+    #[test]
+    fn visible_tiles() {
+        let hand = tiles("M1 M2 M3 P4 P5 P6 S7 S8 S9 EW EW P2 P3");
+        let mut visible = hand.clone();
+        visible.extend(tiles("P1 P1 P1 P4 P4 P4"));
+        let (shanten, accepted) =
+            super::accepted_tiles(&Tile::counts(&hand), 0, &unseen(&visible));
+        assert_eq!(shanten, 0);
+        assert_eq!(
+            accepted,
+            vec![
+                ("P1".parse().unwrap(), 1),
+                ("P4".parse().unwrap(), 0),
+                ("P7".parse().unwrap(), 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn recommendations() {
+        let player = Player::new(
+            Wind::South,
+            tiles("M2 M3 M4 M6 M7 P3 P4 P5 P5 S4 S6 S8 GD GD"),
+        );
+        let doras = tiles("S6");
+        let candidates = recommend_discards(
+            &player,
+            &unseen(&player.hand),
+            &doras,
+            Wind::East,
+        );
+        assert_eq!(candidates.len(), 12);
+
+        // the extra 5 pin keeps both the 468 sou and the kanchan open, the
+        // 4 and 8 sou keep 1 shanten with fewer tiles.
+        let best = &candidates[0];
+        assert_eq!(best.acceptance.discard, tiles("P5")[0]);
+        assert_eq!(best.acceptance.shanten, 1);
+        assert_eq!(best.acceptance.count(), 16);
+        assert!(candidates[1..3]
+            .iter()
+            .all(|candidate| candidate.acceptance.count() == 12));
+        assert_eq!(candidates[3].acceptance.shanten, 2);
+        assert!(best.second_step > 0.0);
+        assert!(best.value.yakus.contains(&Yaku::Riichi));
+        assert_eq!(best.value.dora, 1);
+        assert!(best.explanation.starts_with("1 shanten"));
+
+        // throwing the dora away costs value.
+        let dora = candidates
+            .iter()
+            .find(|candidate| candidate.acceptance.discard == doras[0])
+            .unwrap();
+        assert_eq!(dora.value.dora, 0);
+    }
+}
@@ -0,0 +1,275 @@
+use crate::{
+    round::{RoundEnd, Win},
+    tile::{Tile, Wind},
+};
+
+/// something that happened at the table, in the order it happened. players
+/// are referred to by table position.
+#[derive(Debug, PartialEq, Clone)]
+pub enum GameEvent {
+    /// the hands everyone was dealt, after setting bonus tiles aside.
+    Deal {
+        dealer: usize,
+        prevalent_wind: Wind,
+        hands: Vec<Vec<Tile>>,
+        dora_indicator: Tile,
+    },
+    /// a draw from the live wall, or from the dead wall after a kan, a
+    /// nukidora or a flower or season, which is set aside as it's drawn.
+    Draw {
+        position: usize,
+        tile: Tile,
+    },
+    Discard {
+        position: usize,
+        tile: Tile,
+    },
+    /// follows the declaring discard.
+    Riichi {
+        position: usize,
+        double: bool,
+    },
+    /// a sequence starting with `start` on the discarded `tile`.
+    Chi {
+        caller: usize,
+        discarder: usize,
+        tile: Tile,
+        start: Tile,
+    },
+    Pon {
+        caller: usize,
+        discarder: usize,
+        tile: Tile,
+    },
+    /// an open kan on a discard, or a concealed or added one without a
+    /// discarder.
+    Kan {
+        position: usize,
+        discarder: Option<usize>,
+        tile: Tile,
+    },
+    /// a north set aside in sanma.
+    Nukidora {
+        position: usize,
+    },
+    /// a kan dora indicator turned over.
+    DoraReveal {
+        indicator: Tile,
+    },
+    Win(Win),
+    /// an exhaustive or abortive draw.
+    DrawGame(RoundEnd),
+    /// points moving between the players, for a win, a draw or a riichi
+    /// stick, and the scores after it.
+    ScoreChange {
+        payments: Vec<i32>,
+        scores: Vec<i32>,
+    },
+    /// stepping through a `History` took back every event after the first
+    /// `events` of the round, and those of the branch stepped to follow.
+    /// only sent to subscribers, never logged.
+    Rewind {
+        events: usize,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc::channel;
+
+    use crate::{
+        event::GameEvent,
+        rng::Rng,
+        round::{AbortiveDraw, Round, RoundEnd},
+        ruleset::Ruleset,
+        tile::{tiles, Wind},
+        wall::Wall,
+    };
+
+    fn dealt(ruleset: Ruleset, seed: u64) -> Round {
+        let wall = Wall::shuffled(&ruleset, &mut Rng::new(seed));
+        let scores = vec![25000; ruleset.players];
+        Round::new(ruleset, wall, Wind::East, 0, scores)
+    }
+
+    #[test]
+    fn riichi() {
+        let nw = tiles("NW")[0];
+        let mut round = dealt(Ruleset::riichi(), 5);
+        round.draw().unwrap();
+        round.players[0].hand =
+            tiles("M1 M2 M3 P4 P5 P6 S7 S8 S9 M5 M6 EW EW NW");
+        round.drawn = Some(nw);
+        let events = round.subscribe();
+        events.try_iter().for_each(drop);
+        round.declare_riichi(nw).unwrap();
+        assert_eq!(
+            events.try_iter().collect::<Vec<_>>(),
+            vec![
+                GameEvent::Discard {
+                    position: 0,
+                    tile: nw
+                },
+                GameEvent::Riichi {
+                    position: 0,
+                    double: true
+                },
+            ]
+        );
+
+        // the stick only goes down once nobody wins on the discard.
+        round.resolve().unwrap();
+        assert_eq!(
+            events.try_iter().collect::<Vec<_>>(),
+            vec![GameEvent::ScoreChange {
+                payments: vec![-1000, 0, 0, 0],
+                scores: vec![24000, 25000, 25000, 25000],
+            }]
+        );
+    }
+
+    #[test]
+    fn kans() {
+        let m1 = tiles("M1")[0];
+        let mut round = dealt(Ruleset::riichi(), 5);
+        round.draw().unwrap();
+        round.players[0].hand =
+            tiles("M1 M1 M1 P4 P5 P6 S7 S8 S9 RD RD RD M5 M1");
+        round.drawn = Some(m1);
+        let events = round.subscribe();
+        events.try_iter().for_each(drop);
+        round.declare_kan(m1).unwrap();
+        let received: Vec<GameEvent> = events.try_iter().collect();
+        assert_eq!(
+            received[0],
+            GameEvent::Kan {
+                position: 0,
+                discarder: None,
+                tile: m1
+            }
+        );
+        assert_eq!(
+            received[1],
+            GameEvent::DoraReveal {
+                indicator: round.wall.dora_indicators()[1]
+            }
+        );
+        assert_eq!(
+            received[2],
+            GameEvent::Draw {
+                position: 0,
+                tile: round.drawn.unwrap()
+            }
+        );
+        assert_eq!(received.len(), 3);
+
+        // the fourth tile added to a called triplet.
+        let p5 = tiles("P5")[0];
+        let mut round = dealt(Ruleset::riichi(), 5);
+        round.draw().unwrap();
+        round.players[0].hand = tiles("M1 M2 M3 P4 P6 S7 S8 S9 RD RD P5");
+        round.players[0].dealt_in = vec![p5; 3];
+        let events = round.subscribe();
+        events.try_iter().for_each(drop);
+        round.declare_kan(p5).unwrap();
+        assert_eq!(
+            events.try_iter().next(),
+            Some(GameEvent::Kan {
+                position: 0,
+                discarder: None,
+                tile: p5
+            })
+        );
+    }
+
+    #[test]
+    fn nukidora() {
+        let north = tiles("NW")[0];
+        let mut round = dealt(Ruleset::sanma(), 4);
+        round.draw().unwrap();
+        round.players[0].hand[0] = north;
+        let events = round.subscribe();
+        events.try_iter().for_each(drop);
+        round.declare_nukidora().unwrap();
+        assert_eq!(
+            events.try_iter().collect::<Vec<_>>(),
+            vec![
+                GameEvent::Nukidora { position: 0 },
+                GameEvent::Draw {
+                    position: 0,
+                    tile: round.drawn.unwrap()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn draw_game() {
+        let mut round = dealt(Ruleset::riichi(), 2);
+        round.draw().unwrap();
+        round.players[0].hand =
+            tiles("M1 M9 P1 P9 S1 S9 EW SW M2 M3 M4 P5 P6 WW");
+        let events = round.subscribe();
+        round.declare_nine_terminals().unwrap();
+        let received: Vec<GameEvent> = events.try_iter().collect();
+        assert_eq!(
+            received[received.len() - 2],
+            GameEvent::DrawGame(RoundEnd::AbortiveDraw(
+                AbortiveDraw::NineTerminals
+            ))
+        );
+        assert_eq!(
+            received.last(),
+            Some(&GameEvent::ScoreChange {
+                payments: vec![0; 4],
+                scores: vec![25000; 4],
+            })
+        );
+
+        let mut round = dealt(Ruleset::riichi(), 7);
+        let events = round.subscribe();
+        while let Some(tile) = round.draw().unwrap() {
+            round.discard(tile).unwrap();
+            round.resolve().unwrap();
+        }
+        let result = round.result.clone().unwrap();
+        let received: Vec<GameEvent> = events.try_iter().collect();
+        assert_eq!(
+            received[received.len() - 2],
+            GameEvent::DrawGame(result.end)
+        );
+        assert_eq!(
+            received.last(),
+            Some(&GameEvent::ScoreChange {
+                payments: result.payments,
+                scores: round.scores.clone(),
+            })
+        );
+    }
+
+    #[test]
+    fn late_subscriber() {
+        let mut round = dealt(Ruleset::riichi(), 3);
+        for _ in 0..2 {
+            let tile = round.draw().unwrap().unwrap();
+            round.discard(tile).unwrap();
+            round.resolve().unwrap();
+        }
+
+        // everything so far is replayed before the next event.
+        let (sender, receiver) = channel();
+        assert!(round.add_subscriber(sender));
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), round.events);
+        let tile = round.draw().unwrap().unwrap();
+        assert_eq!(
+            receiver.try_iter().collect::<Vec<_>>(),
+            vec![GameEvent::Draw { position: 2, tile }]
+        );
+
+        // one whose receiver is gone isn't kept.
+        let (sender, receiver) = channel();
+        drop(receiver);
+        assert!(!round.add_subscriber(sender));
+        assert_eq!(round.subscribers.len(), 1);
+    }
+}
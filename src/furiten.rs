@@ -0,0 +1,136 @@
+use std::fmt::Display;
+
+use crate::{player::Player, tile::Tile};
+
+/// why a player may not win on someone else's discard. a self-drawn win
+/// is always allowed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Furiten {
+    /// one of the player's winning tiles is among their own discards.
+    Permanent,
+    /// the player let a winning tile go by since their last turn.
+    Temporary,
+    /// the player let a winning tile go by after declaring riichi, which
+    /// lasts until the end of the round.
+    Riichi,
+}
+
+impl Display for Furiten {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Furiten::Permanent => write!(f, "permanent furiten"),
+            Furiten::Temporary => write!(f, "temporary furiten"),
+            Furiten::Riichi => write!(f, "riichi furiten"),
+        }
+    }
+}
+
+/// the part of a player's furiten that can't be read off their river: the
+/// winning tiles they passed on. a round keeps one per player.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct FuritenTracker {
+    temporary: bool,
+    riichi: bool,
+}
+
+impl FuritenTracker {
+    pub fn new() -> FuritenTracker {
+        FuritenTracker::default()
+    }
+
+    /// the player didn't win on `tile`, a discard of someone else or in
+    /// riichi also their own draw. does nothing unless it was a winning
+    /// tile.
+    pub fn pass(&mut self, player: &Player, tile: Tile) {
+        if !player.waits().iter().any(|wait| wait.tile == tile) {
+            return;
+        }
+        self.temporary = true;
+        if player.riichi {
+            self.riichi = true;
+        }
+    }
+
+    /// the player's own turn came around, which ends temporary furiten.
+    pub fn next_turn(&mut self) {
+        self.temporary = false;
+    }
+
+    /// the furiten the player is in while waiting, the one lasting longest
+    /// when there are several.
+    pub fn furiten(&self, player: &Player) -> Option<Furiten> {
+        let waits = player.waits();
+        let river = player.river();
+        if waits.iter().any(|wait| river.contains(&wait.tile)) {
+            Some(Furiten::Permanent)
+        } else if self.riichi {
+            Some(Furiten::Riichi)
+        } else if self.temporary {
+            Some(Furiten::Temporary)
+        } else {
+            None
+        }
+    }
+
+    /// whether the player may win on someone else's discard of `tile`.
+    pub fn can_ron(&self, player: &Player, tile: Tile) -> bool {
+        player.waits().iter().any(|wait| wait.tile == tile)
+            && self.furiten(player).is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        furiten::{Furiten, FuritenTracker},
+        player::Player,
+        tile::{tiles, Wind},
+    };
+
+    fn player(hand: &str, discarded: &str) -> Player {
+        Player {
+            discarded: tiles(discarded),
+            ..Player::new(Wind::South, tiles(hand))
+        }
+    }
+
+    #[test]
+    fn permanent() {
+        let p2 = tiles("P2")[0];
+        let p5 = tiles("P5")[0];
+
+        // discarding the 5 pin rules out a ron on the 2 pin as well.
+        let player = player("M1 M2 M3 M4 M5 M6 S7 S8 S9 EW EW P3 P4", "P5");
+        let tracker = FuritenTracker::new();
+        assert_eq!(tracker.furiten(&player), Some(Furiten::Permanent));
+        assert!(!tracker.can_ron(&player, p2));
+
+        let mut player = player;
+        player.discarded.clear();
+        assert!(tracker.can_ron(&player, p2));
+        // a discard called away still counts.
+        player.called.push(p5);
+        assert_eq!(tracker.furiten(&player), Some(Furiten::Permanent));
+    }
+
+    #[test]
+    fn passing() {
+        let p2 = tiles("P2")[0];
+        let mut player = player("M1 M2 M3 M4 M5 M6 S7 S8 S9 EW EW P3 P4", "");
+        let mut tracker = FuritenTracker::new();
+
+        tracker.pass(&player, tiles("P6")[0]);
+        assert_eq!(tracker.furiten(&player), None);
+
+        tracker.pass(&player, p2);
+        assert_eq!(tracker.furiten(&player), Some(Furiten::Temporary));
+        assert!(!tracker.can_ron(&player, tiles("P5")[0]));
+        tracker.next_turn();
+        assert!(tracker.can_ron(&player, p2));
+
+        player.riichi = true;
+        tracker.pass(&player, p2);
+        tracker.next_turn();
+        assert_eq!(tracker.furiten(&player), Some(Furiten::Riichi));
+    }
+}
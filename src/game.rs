@@ -0,0 +1,393 @@
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+use crate::{
+    event::GameEvent,
+    riichi::RIICHI_STICK,
+    rng::Rng,
+    round::{Round, RoundEnd},
+    ruleset::{GameLength, Ruleset},
+    tile::Wind,
+    wall::Wall,
+};
+
+const WINDS: [Wind; 4] = [Wind::East, Wind::South, Wind::West, Wind::North];
+
+/// where a player finished the game.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Placement {
+    /// the table position.
+    pub position: usize,
+    pub score: i32,
+    /// the score counted from the target points, with the uma for the
+    /// placement, and the oka and riichi sticks left on the table for first.
+    pub result: i32,
+}
+
+/// a match of rounds, moving the dealer around the table and the
+/// prevalent wind on after every player has dealt. deal a round with
+/// `deal`, play it out and hand it back to `settle`.
+#[derive(Clone)]
+pub struct Game {
+    pub ruleset: Ruleset,
+    pub rng: Rng,
+    /// by table position.
+    pub scores: Vec<i32>,
+    pub prevalent_wind: Wind,
+    pub dealer: usize,
+    /// the dealers that have passed in the round of the prevalent wind, the
+    /// round being east 1 with none.
+    pub hand: usize,
+    pub honba: u32,
+    /// the riichi sticks left on the table by draws.
+    pub riichi_sticks: u32,
+    pub finished: bool,
+    /// passed on to every round dealt, see `Round::subscribe`.
+    pub subscribers: Vec<Sender<GameEvent>>,
+}
+
+impl Game {
+    /// a game between the ruleset's players, the one at table position 0
+    /// dealing first.
+    pub fn new(ruleset: Ruleset, rng: Rng) -> Game {
+        Game {
+            scores: vec![ruleset.starting_points; ruleset.players],
+            ruleset,
+            rng,
+            prevalent_wind: Wind::East,
+            dealer: 0,
+            hand: 0,
+            honba: 0,
+            riichi_sticks: 0,
+            finished: false,
+            subscribers: vec![],
+        }
+    }
+
+    /// the wind of the last regular round, after which the game only goes
+    /// on when nobody has reached the target.
+    pub fn last_wind(&self) -> Wind {
+        match self.ruleset.length {
+            GameLength::EastOnly => Wind::East,
+            GameLength::EastSouth => Wind::South,
+        }
+    }
+
+    /// whether this is the final hand of the last regular round (all last).
+    pub fn is_all_last(&self) -> bool {
+        self.prevalent_wind == self.last_wind()
+            && self.hand == self.scores.len() - 1
+    }
+
+    /// whether the game went on past the last regular round, ending as soon
+    /// as someone reaches the target.
+    pub fn is_extension(&self) -> bool {
+        self.prevalent_wind > self.last_wind()
+    }
+
+    /// a stream of the events of every round dealt from now on.
+    pub fn subscribe(&mut self) -> Receiver<GameEvent> {
+        let (sender, receiver) = channel();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    /// shuffles a wall and deals the next round, with the honba and riichi
+    /// sticks carried over. none once the game is over.
+    pub fn deal(&mut self) -> Option<Round> {
+        if self.finished {
+            return None;
+        }
+        let wall = Wall::shuffled(&self.ruleset, &mut self.rng);
+        let mut round = Round::new(
+            self.ruleset.clone(),
+            wall,
+            self.prevalent_wind,
+            self.dealer,
+            self.scores.clone(),
+        );
+        round.honba = self.honba;
+        round.riichi_sticks = self.riichi_sticks;
+        self.subscribers
+            .retain(|subscriber| round.add_subscriber(subscriber.clone()));
+        Some(round)
+    }
+
+    /// takes the scores of a round that ended, moves the dealer and the
+    /// prevalent wind on and decides whether the game is over.
+    pub fn settle(&mut self, round: &Round) {
+        let Some(result) = &round.result else {
+            return;
+        };
+        self.scores.clone_from(&round.scores);
+        self.riichi_sticks = round.riichi_sticks;
+
+        let won = matches!(result.end, RoundEnd::Win(_));
+        self.honba = if won && !result.dealer_keeps {
+            0
+        } else {
+            self.honba + 1
+        };
+
+        if self.ruleset.busting && self.scores.iter().any(|score| *score < 0) {
+            self.finished = true;
+            return;
+        }
+
+        if result.dealer_keeps {
+            let aborted = matches!(result.end, RoundEnd::AbortiveDraw(_));
+            if self.is_all_last()
+                && !aborted
+                && self.ruleset.agari_yame
+                && self.placements()[0].position == self.dealer
+                && self.scores[self.dealer] >= self.ruleset.target_points
+            {
+                self.finished = true;
+                return;
+            }
+        } else {
+            self.dealer = (self.dealer + 1) % self.scores.len();
+            self.hand += 1;
+            if self.hand == self.scores.len() {
+                self.hand = 0;
+                let wind = WINDS
+                    .iter()
+                    .position(|wind| *wind == self.prevalent_wind)
+                    .unwrap_or_default();
+                self.prevalent_wind = WINDS[(wind + 1) % WINDS.len()];
+            }
+        }
+
+        if self.is_extension() {
+            let reached = self
+                .scores
+                .iter()
+                .any(|score| *score >= self.ruleset.target_points);
+            // one extra round of winds at most.
+            let extension = WINDS[self.last_wind() as usize + 1];
+            self.finished = reached || self.prevalent_wind > extension;
+        }
+    }
+
+    /// the players from first to last, ties going to whoever sits closest to
+    /// the first dealer.
+    pub fn placements(&self) -> Vec<Placement> {
+        let mut order: Vec<usize> = (0..self.scores.len()).collect();
+        order.sort_by(|first, second| {
+            self.scores[*second].cmp(&self.scores[*first])
+        });
+
+        order
+            .into_iter()
+            .enumerate()
+            .map(|(place, position)| {
+                let score = self.scores[position];
+                let mut result = score - self.ruleset.target_points
+                    + self.ruleset.uma.get(place).copied().unwrap_or_default();
+                if place == 0 {
+                    result += self.ruleset.oka(self.scores.len())
+                        + self.riichi_sticks as i32 * RIICHI_STICK;
+                }
+                Placement {
+                    position,
+                    score,
+                    result,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        event::GameEvent,
+        game::Game,
+        rng::Rng,
+        round::{AbortiveDraw, RoundEnd, RoundResult, Win},
+        ruleset::{GameLength, Ruleset},
+        score::Score,
+        tile::Wind,
+    };
+
+    fn started(length: GameLength) -> Game {
+        let ruleset = Ruleset {
+            length,
+            ..Ruleset::riichi()
+        };
+        Game::new(ruleset, Rng::new(5))
+    }
+
+    fn won(winner: usize) -> RoundEnd {
+        RoundEnd::Win(vec![Win {
+            winner,
+            discarder: None,
+            yakus: vec![],
+            score: Score::new(1, 30),
+        }])
+    }
+
+    /// deals a round and ends it with `end`, moving `payments` around.
+    fn play(game: &mut Game, end: RoundEnd, payments: [i32; 4]) {
+        let mut round = game.deal().unwrap();
+        for (score, paid) in round.scores.iter_mut().zip(payments) {
+            *score += paid;
+        }
+        let dealer_keeps = match &end {
+            RoundEnd::Win(wins) => wins[0].winner == round.dealer,
+            RoundEnd::ExhaustiveDraw { tenpai, .. } => {
+                tenpai.contains(&round.dealer)
+            }
+            RoundEnd::AbortiveDraw(_) => true,
+        };
+        round.result = Some(RoundResult {
+            end,
+            payments: payments.to_vec(),
+            dealer_keeps,
+        });
+        game.settle(&round);
+    }
+
+    fn draw(tenpai: Vec<usize>) -> RoundEnd {
+        RoundEnd::ExhaustiveDraw {
+            tenpai,
+            nagashi: vec![],
+        }
+    }
+
+    #[test]
+    fn dealer_rotation() {
+        let mut game = started(GameLength::EastOnly);
+        assert_eq!(game.scores, vec![25000; 4]);
+
+        // the dealer repeats on a win and a tenpai draw, with a honba each.
+        play(&mut game, won(0), [3000, -1000, -1000, -1000]);
+        assert_eq!((game.dealer, game.honba), (0, 1));
+        play(&mut game, draw(vec![0]), [3000, -1000, -1000, -1000]);
+        assert_eq!((game.dealer, game.honba), (0, 2));
+        let round = game.deal().unwrap();
+        assert_eq!((round.honba, round.dealer), (2, 0));
+
+        // a noten draw passes the deal on but keeps counting.
+        play(&mut game, draw(vec![1]), [-1000, 3000, -1000, -1000]);
+        assert_eq!((game.dealer, game.hand, game.honba), (1, 1, 3));
+        play(&mut game, won(2), [0, -2000, 2000, 0]);
+        assert_eq!((game.dealer, game.honba), (2, 0));
+        play(&mut game, won(2), [0, 0, 1000, -1000]);
+        assert_eq!(game.dealer, 2);
+        play(&mut game, won(0), [1000, 0, -1000, 0]);
+        assert!(game.is_all_last());
+
+        // someone above the target ends the game after all last.
+        play(&mut game, won(0), [1000, 0, 0, -1000]);
+        assert!(game.finished);
+        assert!(game.deal().is_none());
+    }
+
+    #[test]
+    fn game_end() {
+        // nobody reaching the target takes an east-south game into west,
+        // which ends as soon as someone does.
+        let mut game = started(GameLength::EastSouth);
+        for winner in [1, 2, 3, 0, 1, 2, 3, 0] {
+            play(&mut game, won(winner), [0; 4]);
+        }
+        assert_eq!((game.prevalent_wind, game.hand), (Wind::West, 0));
+        assert!(game.is_extension() && !game.finished);
+        play(&mut game, won(1), [-3000, 3000, 0, 0]);
+        assert!(!game.finished);
+        play(&mut game, won(1), [0, 2000, -2000, 0]);
+        assert!(game.finished);
+
+        // the dealer stops on a win in all last while in first place.
+        let mut game = started(GameLength::EastOnly);
+        play(&mut game, won(1), [0; 4]);
+        play(&mut game, won(2), [0; 4]);
+        play(&mut game, won(3), [0; 4]);
+        play(&mut game, won(3), [-2000, -2000, -2000, 6000]);
+        assert!(game.finished);
+
+        // an abortive draw doesn't count as an agari.
+        let mut game = started(GameLength::EastOnly);
+        game.dealer = 3;
+        game.hand = 3;
+        game.scores[3] += 6000;
+        game.scores[0] -= 6000;
+        play(
+            &mut game,
+            RoundEnd::AbortiveDraw(AbortiveDraw::FourWinds),
+            [0; 4],
+        );
+        assert!(!game.finished);
+
+        // dropping below zero ends it right away.
+        let mut game = started(GameLength::EastSouth);
+        play(&mut game, won(1), [0, 26000, 0, -26000]);
+        assert!(game.finished);
+    }
+
+    #[test]
+    fn placements() {
+        let mut game = started(GameLength::EastOnly);
+        game.scores = vec![20000, 42000, 20000, 17000];
+        game.riichi_sticks = 1;
+        let placements = game.placements();
+        assert_eq!(
+            placements
+                .iter()
+                .map(|placement| placement.position)
+                .collect::<Vec<_>>(),
+            vec![1, 0, 2, 3]
+        );
+        assert_eq!(placements[0].result, 12000 + 20000 + 20000 + 1000);
+        assert_eq!(placements[1].result, -10000 + 10000);
+        assert_eq!(placements[3].result, -13000 - 20000);
+        assert_eq!(
+            placements
+                .iter()
+                .map(|placement| placement.result)
+                .sum::<i32>(),
+            0
+        );
+    }
+
+    #[test]
+    fn sanma() {
+        let mut game = Game::new(Ruleset::sanma(), Rng::new(8));
+        assert_eq!(game.scores, vec![35000; 3]);
+        let round = game.deal().unwrap();
+        assert_eq!(round.players.len(), 3);
+
+        // south comes after each of the three players dealt.
+        for _ in 0..3 {
+            let mut round = game.deal().unwrap();
+            round.result = Some(RoundResult {
+                end: won(1),
+                payments: vec![0; 3],
+                dealer_keeps: false,
+            });
+            game.settle(&round);
+        }
+        assert_eq!((game.prevalent_wind, game.dealer), (Wind::South, 0));
+
+        game.scores = vec![50000, 35000, 20000];
+        let placements = game.placements();
+        assert_eq!(placements[0].result, 10000 + 15000 + 15000);
+        assert_eq!(placements[2].result, -20000 - 15000);
+    }
+
+    #[test]
+    fn events() {
+        let mut game = started(GameLength::EastOnly);
+        let events = game.subscribe();
+        play(&mut game, won(1), [0; 4]);
+        play(&mut game, won(2), [0; 4]);
+        let deals: Vec<usize> = events
+            .try_iter()
+            .filter_map(|event| match event {
+                GameEvent::Deal { dealer, .. } => Some(dealer),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(deals, vec![0, 1]);
+    }
+}
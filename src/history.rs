@@ -0,0 +1,472 @@
+use std::{fmt::Display, mem};
+
+use crate::{
+    event::GameEvent,
+    player::Player,
+    round::{Claim, Round, RoundError},
+    tile::Tile,
+};
+
+/// a move in a round, which `History` records to step back and forth
+/// through.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Action {
+    Draw,
+    Discard(Tile),
+    Riichi(Tile),
+    Kan(Tile),
+    Nukidora,
+    NineTerminals,
+    Tsumo,
+    /// a claim by the player at a table position.
+    Claim(usize, Claim),
+    Resolve,
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Action::Draw => write!(f, "draw"),
+            Action::Discard(tile) => write!(f, "discard {}", tile),
+            Action::Riichi(tile) => write!(f, "riichi {}", tile),
+            Action::Kan(tile) => write!(f, "kan {}", tile),
+            Action::Nukidora => write!(f, "nuki"),
+            Action::NineTerminals => write!(f, "abort"),
+            Action::Tsumo => write!(f, "tsumo"),
+            Action::Claim(position, claim) => {
+                write!(f, "{} claims {}", position, claim)
+            }
+            Action::Resolve => write!(f, "resolve"),
+        }
+    }
+}
+
+impl Action {
+    pub fn apply(self, round: &mut Round) -> Result<(), RoundError> {
+        match self {
+            Action::Draw => round.draw().map(|_| ()),
+            Action::Discard(tile) => round.discard(tile),
+            Action::Riichi(tile) => round.declare_riichi(tile),
+            Action::Kan(tile) => round.declare_kan(tile),
+            Action::Nukidora => round.declare_nukidora(),
+            Action::NineTerminals => round.declare_nine_terminals(),
+            Action::Tsumo => round.tsumo().map(|_| ()),
+            Action::Claim(position, claim) => round.claim(position, claim),
+            Action::Resolve => round.resolve(),
+        }
+    }
+}
+
+/// a move in a hand under analysis, where the tiles drawn are the ones
+/// asked about rather than those of a wall.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Change {
+    Draw(Tile),
+    Discard(Tile),
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::Draw(tile) => write!(f, "draw {}", tile),
+            Change::Discard(tile) => write!(f, "discard {}", tile),
+        }
+    }
+}
+
+impl Change {
+    pub fn apply(self, player: &mut Player) -> Result<(), RoundError> {
+        match self {
+            Change::Draw(tile) => player.hand.push(tile),
+            Change::Discard(tile) => {
+                let position = player
+                    .hand
+                    .iter()
+                    .position(|t| *t == tile)
+                    .ok_or(RoundError::NotInHand(tile))?;
+                player.hand.remove(position);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// a state `History` keeps a copy of after every move.
+pub trait Snapshot: Clone {
+    /// the copy kept in a node, without what only the current state needs.
+    /// `before` is the current state the move was made on, none at the
+    /// start.
+    fn snapshot(&self, before: Option<&Self>) -> Self {
+        let _ = before;
+        self.clone()
+    }
+
+    /// steps from this current state to the one kept last on `path`, the
+    /// kept states from the start.
+    fn restore(&mut self, path: &[&Self]) {
+        *self = path[path.len() - 1].clone();
+    }
+}
+
+/// a state and how it was reached.
+#[derive(Clone)]
+pub struct Node<S, A> {
+    /// as `Snapshot::snapshot` keeps it.
+    pub state: S,
+    /// the move from the parent, none for the start.
+    pub action: Option<A>,
+    pub parent: Option<usize>,
+    /// one per alternative tried, in the order they were first played.
+    pub children: Vec<usize>,
+    /// the child `redo` goes to, the one last played or stepped through.
+    redo: Option<usize>,
+}
+
+impl<S, A> Node<S, A> {
+    /// the child `redo` goes to.
+    pub fn next(&self) -> Option<usize> {
+        self.redo
+    }
+}
+
+/// a tree of snapshots of a state, like a round or a hand under analysis,
+/// with a node after every move. stepping back and playing a different
+/// move starts a new branch next to the old one. a round's snapshot holds
+/// its wall as well, so every branch draws from the same tiles.
+#[derive(Clone)]
+pub struct History<S, A> {
+    nodes: Vec<Node<S, A>>,
+    current: usize,
+    /// the state at `current` in full.
+    live: S,
+}
+
+impl<S: Snapshot, A: Clone + PartialEq> History<S, A> {
+    pub fn new(state: S) -> History<S, A> {
+        History {
+            nodes: vec![Node {
+                state: state.snapshot(None),
+                action: None,
+                parent: None,
+                children: vec![],
+                redo: None,
+            }],
+            current: 0,
+            live: state,
+        }
+    }
+
+    pub fn current(&self) -> &S {
+        &self.live
+    }
+
+    /// the node of the current state.
+    pub fn position(&self) -> usize {
+        self.current
+    }
+
+    pub fn node(&self, node: usize) -> Option<&Node<S, A>> {
+        self.nodes.get(node)
+    }
+
+    /// makes `action` on a copy of the current state with `apply` and moves
+    /// on to the result, or to the existing node when the move was played
+    /// from here before. nothing is recorded when `apply` fails.
+    pub fn play<E>(
+        &mut self,
+        action: A,
+        apply: impl FnOnce(&mut S, &A) -> Result<(), E>,
+    ) -> Result<usize, E> {
+        let played = self.nodes[self.current]
+            .children
+            .iter()
+            .find(|child| self.nodes[**child].action.as_ref() == Some(&action));
+        if let Some(&child) = played {
+            self.go_to(child);
+            return Ok(child);
+        }
+
+        let mut state = self.live.clone();
+        apply(&mut state, &action)?;
+        let child = self.nodes.len();
+        self.nodes.push(Node {
+            state: state.snapshot(Some(&self.live)),
+            action: Some(action),
+            parent: Some(self.current),
+            children: vec![],
+            redo: None,
+        });
+        let parent = &mut self.nodes[self.current];
+        parent.children.push(child);
+        parent.redo = Some(child);
+        self.current = child;
+        self.live = state;
+        Ok(child)
+    }
+
+    /// steps back to the state before the last move, false at the start.
+    pub fn undo(&mut self) -> bool {
+        match self.nodes[self.current].parent {
+            Some(parent) => {
+                self.step_to(parent);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// steps forward along the branch last played or stepped through,
+    /// false when there's none.
+    pub fn redo(&mut self) -> bool {
+        match self.nodes[self.current].redo {
+            Some(child) => {
+                self.step_to(child);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// jumps to any node, which `redo` then leads back to from each of its
+    /// ancestors. false when there's no such node.
+    pub fn go_to(&mut self, node: usize) -> bool {
+        if node >= self.nodes.len() {
+            return false;
+        }
+        let mut child = node;
+        while let Some(parent) = self.nodes[child].parent {
+            self.nodes[parent].redo = Some(child);
+            child = parent;
+        }
+        self.step_to(node);
+        true
+    }
+
+    fn step_to(&mut self, node: usize) {
+        let mut path = vec![&self.nodes[node].state];
+        let mut child = node;
+        while let Some(parent) = self.nodes[child].parent {
+            path.push(&self.nodes[parent].state);
+            child = parent;
+        }
+        path.reverse();
+        self.live.restore(&path);
+        self.current = node;
+    }
+
+    /// the moves from the start to the current state.
+    pub fn path(&self) -> Vec<A> {
+        let mut actions = vec![];
+        let mut node = &self.nodes[self.current];
+        while let (Some(action), Some(parent)) = (&node.action, node.parent) {
+            actions.push(action.clone());
+            node = &self.nodes[parent];
+        }
+        actions.reverse();
+        actions
+    }
+}
+
+/// the kept rounds hold neither subscribers nor the events before the move,
+/// which the current round gathers back along the path. its subscribers
+/// stay with it and get a `GameEvent::Rewind` when a step takes events
+/// back.
+impl Snapshot for Round {
+    fn snapshot(&self, before: Option<&Round>) -> Round {
+        let logged = before.map_or(0, |round| round.events.len());
+        let mut round = self.clone();
+        round.events.drain(..logged);
+        round.subscribers.clear();
+        round
+    }
+
+    fn restore(&mut self, path: &[&Round]) {
+        let events: Vec<GameEvent> = path
+            .iter()
+            .flat_map(|round| round.events.iter().cloned())
+            .collect();
+        let kept = self
+            .events
+            .iter()
+            .zip(&events)
+            .take_while(|(old, new)| old == new)
+            .count();
+        let mut sent = vec![];
+        if kept < self.events.len() {
+            sent.push(GameEvent::Rewind { events: kept });
+        }
+        sent.extend(events[kept..].iter().cloned());
+
+        let subscribers = mem::take(&mut self.subscribers);
+        *self = path[path.len() - 1].clone();
+        self.events = events;
+        self.subscribers = subscribers;
+        self.subscribers.retain(|subscriber| {
+            sent.iter()
+                .all(|event| subscriber.send(event.clone()).is_ok())
+        });
+    }
+}
+
+/// a player is kept whole.
+impl Snapshot for Player {}
+
+impl History<Player, Change> {
+    /// makes `change` to the hand under analysis, see `play`.
+    pub fn change(&mut self, change: Change) -> Result<usize, RoundError> {
+        self.play(change, |player, change| change.apply(player))
+    }
+}
+
+/// a round's history ends where the round does: a game moves on with
+/// `Game::deal` and `Game::settle`, which aren't moves in it.
+impl History<Round, Action> {
+    /// plays `action` in the current round, see `play`.
+    pub fn act(&mut self, action: Action) -> Result<usize, RoundError> {
+        self.play(action, |round, action| action.apply(round))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        event::GameEvent,
+        history::{Action, Change, History},
+        player::Player,
+        rng::Rng,
+        round::{Phase, Round, RoundError},
+        ruleset::Ruleset,
+        tile::{tiles, Wind},
+        wall::Wall,
+    };
+
+    fn dealt(seed: u64) -> Round {
+        let ruleset = Ruleset::riichi();
+        let wall = Wall::shuffled(&ruleset, &mut Rng::new(seed));
+        Round::new(ruleset, wall, Wind::East, 0, vec![25000; 4])
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut history = History::new(dealt(3));
+        history.act(Action::Draw).unwrap();
+        let hand = history.current().current().hand.clone();
+        history.act(Action::Discard(hand[0])).unwrap();
+        history.act(Action::Resolve).unwrap();
+        assert_eq!(history.current().turn, 1);
+
+        assert!(history.undo());
+        assert!(history.undo());
+        assert_eq!(history.current().phase, Phase::Discard);
+        assert_eq!(history.current().current().hand, hand);
+        assert!(history.redo());
+        assert!(history.redo());
+        assert!(!history.redo());
+        assert_eq!(
+            history.path(),
+            vec![Action::Draw, Action::Discard(hand[0]), Action::Resolve]
+        );
+
+        // a move that fails leaves no trace.
+        let before = history.position();
+        assert_eq!(
+            history.act(Action::Tsumo),
+            Err(RoundError::WrongPhase(Phase::Draw))
+        );
+        assert_eq!(history.position(), before);
+
+        history.go_to(0);
+        assert!(!history.undo());
+        assert!(history.redo());
+        assert_eq!(history.path(), vec![Action::Draw]);
+    }
+
+    #[test]
+    fn branches() {
+        let mut history = History::new(dealt(4));
+        let drawn = history.act(Action::Draw).unwrap();
+        let hand = history.current().current().hand.clone();
+
+        let mut draws = vec![];
+        for tile in [hand[0], hand[1]] {
+            history.go_to(drawn);
+            history.act(Action::Discard(tile)).unwrap();
+            history.act(Action::Resolve).unwrap();
+            history.act(Action::Draw).unwrap();
+            draws.push(history.current().drawn);
+        }
+        let children = history.node(drawn).unwrap().children.clone();
+        assert_eq!(children.len(), 2);
+        // the wall is the same in every branch.
+        assert_eq!(draws[0], draws[1]);
+
+        // playing a move again follows the branch already there.
+        history.go_to(drawn);
+        let again = history.act(Action::Discard(hand[0])).unwrap();
+        assert_eq!(again, children[0]);
+        assert!(history.redo());
+        assert_eq!(history.current().turn, 1);
+    }
+
+    #[test]
+    fn subscribers() {
+        let mut round = dealt(4);
+        let events = round.subscribe();
+        let mut history = History::new(round);
+        let drawn = history.act(Action::Draw).unwrap();
+        let hand = history.current().current().hand.clone();
+        history.act(Action::Discard(hand[0])).unwrap();
+        events.try_iter().for_each(drop);
+
+        // only the current round sends, and a step back says so first.
+        history.go_to(drawn);
+        let discarded = history.act(Action::Discard(hand[1])).unwrap();
+        let discard = |tile| GameEvent::Discard { position: 0, tile };
+        assert_eq!(
+            events.try_iter().collect::<Vec<_>>(),
+            vec![GameEvent::Rewind { events: 2 }, discard(hand[1])]
+        );
+        assert_eq!(history.current().events.len(), 3);
+        assert_eq!(history.current().events[2], discard(hand[1]));
+
+        // stepping forward again sends what's stepped over.
+        assert!(history.undo());
+        assert!(history.undo());
+        events.try_iter().for_each(drop);
+        history.go_to(discarded);
+        assert_eq!(
+            events.try_iter().collect::<Vec<_>>(),
+            vec![history.current().events[1].clone(), discard(hand[1])]
+        );
+
+        // the kept rounds hold only the events of their move.
+        let node = history.node(discarded).unwrap();
+        assert!(node.state.subscribers.is_empty());
+        assert_eq!(node.state.events, vec![discard(hand[1])]);
+        assert_eq!(history.current().subscribers.len(), 1);
+    }
+
+    #[test]
+    fn analysis() {
+        let hand = tiles("M1 M2 M3 P4 P5 P6 S7 S8 S9 EW EW RD GD NW");
+        let mut history = History::new(Player::new(Wind::East, hand.clone()));
+        let discarded = history.change(Change::Discard(hand[13])).unwrap();
+        history.change(Change::Draw(hand[11])).unwrap();
+        assert_eq!(
+            history.change(Change::Discard(hand[13])),
+            Err(RoundError::NotInHand(hand[13]))
+        );
+
+        // another draw after the same discard is a branch of its own.
+        assert!(history.undo());
+        history.change(Change::Draw(hand[12])).unwrap();
+        assert_eq!(history.node(discarded).unwrap().children.len(), 2);
+        assert_eq!(
+            history.path(),
+            vec![Change::Discard(hand[13]), Change::Draw(hand[12])]
+        );
+        assert!(history.undo());
+        assert!(history.undo());
+        assert_eq!(history.current().hand, hand);
+    }
+}
@@ -0,0 +1,928 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    player::{Player, WinContext},
+    round::{AbortiveDraw, RoundEnd, RoundResult, Win},
+    score::{Limit, Score, TsumoPayment},
+    tile::{Dragon, Tile, Wind},
+    yaku::Yaku,
+};
+
+/// a parsed JSON document. objects keep their keys in insertion order so the
+/// output of `Display` is stable.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct JsonError(pub String);
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid JSON: {}", self.0)
+    }
+}
+
+pub trait ToJson {
+    fn to_json(&self) -> Json;
+}
+
+pub trait FromJson: Sized {
+    fn from_json(json: &Json) -> Result<Self, JsonError>;
+}
+
+impl Json {
+    pub fn get(&self, key: &str) -> Result<&Json, JsonError> {
+        match self {
+            Json::Object(fields) => fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value)
+                .ok_or_else(|| JsonError(format!("missing field `{}`", key))),
+            _ => Err(JsonError(format!("expected an object with `{}`", key))),
+        }
+    }
+
+    pub fn as_str(&self) -> Result<&str, JsonError> {
+        match self {
+            Json::String(string) => Ok(string),
+            _ => Err(JsonError("expected a string".to_string())),
+        }
+    }
+
+    pub fn as_bool(&self) -> Result<bool, JsonError> {
+        match self {
+            Json::Bool(value) => Ok(*value),
+            _ => Err(JsonError("expected a boolean".to_string())),
+        }
+    }
+
+    pub fn as_i64(&self) -> Result<i64, JsonError> {
+        match self {
+            Json::Number(number) if number.fract() == 0.0 => Ok(*number as i64),
+            _ => Err(JsonError("expected an integer".to_string())),
+        }
+    }
+
+    pub fn as_array(&self) -> Result<&[Json], JsonError> {
+        match self {
+            Json::Array(values) => Ok(values),
+            _ => Err(JsonError("expected an array".to_string())),
+        }
+    }
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(number) => write!(f, "{}", number),
+            Json::String(string) => {
+                write!(f, "\"")?;
+                for c in string.chars() {
+                    match c {
+                        '"' => write!(f, "\\\"")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        '\r' => write!(f, "\\r")?,
+                        '\t' => write!(f, "\\t")?,
+                        c if (c as u32) < 0x20 => {
+                            write!(f, "\\u{:04x}", c as u32)?
+                        }
+                        c => write!(f, "{}", c)?,
+                    }
+                }
+                write!(f, "\"")
+            }
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in fields.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}:{}", Json::String(key.clone()), value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+impl FromStr for Json {
+    type Err = JsonError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            chars: s.chars().collect(),
+            position: 0,
+        };
+        let value = parser.value()?;
+        parser.whitespace();
+        if parser.position != parser.chars.len() {
+            return Err(parser.error("trailing characters"));
+        }
+        Ok(value)
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &str) -> JsonError {
+        JsonError(format!("{} at {}", message, self.position))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, literal: &str) -> Result<(), JsonError> {
+        for c in literal.chars() {
+            if self.peek() != Some(c) {
+                return Err(self.error(&format!("expected `{}`", literal)));
+            }
+            self.position += 1;
+        }
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<Json, JsonError> {
+        self.whitespace();
+        match self.peek() {
+            Some('n') => self.expect("null").map(|_| Json::Null),
+            Some('t') => self.expect("true").map(|_| Json::Bool(true)),
+            Some('f') => self.expect("false").map(|_| Json::Bool(false)),
+            Some('"') => self.string().map(Json::String),
+            Some('[') => self.array(),
+            Some('{') => self.object(),
+            Some('-' | '0'..='9') => self.number(),
+            _ => Err(self.error("unexpected character")),
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, JsonError> {
+        let start = self.position;
+        while matches!(
+            self.peek(),
+            Some('-' | '+' | '.' | 'e' | 'E' | '0'..='9')
+        ) {
+            self.position += 1;
+        }
+        let literal: String = self.chars[start..self.position].iter().collect();
+        literal
+            .parse()
+            .map(Json::Number)
+            .map_err(|_| self.error("invalid number"))
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.expect("\"")?;
+        let mut string = String::new();
+        loop {
+            let c = self
+                .peek()
+                .ok_or_else(|| self.error("unterminated string"))?;
+            self.position += 1;
+            match c {
+                '"' => return Ok(string),
+                '\\' => {
+                    let escaped =
+                        self.peek().ok_or_else(|| self.error("bad escape"))?;
+                    self.position += 1;
+                    string.push(match escaped {
+                        '"' => '"',
+                        '\\' => '\\',
+                        '/' => '/',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'u' => {
+                            let digits: String = self
+                                .chars
+                                .get(self.position..self.position + 4)
+                                .ok_or_else(|| self.error("bad escape"))?
+                                .iter()
+                                .collect();
+                            self.position += 4;
+                            u32::from_str_radix(&digits, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| self.error("bad escape"))?
+                        }
+                        _ => return Err(self.error("bad escape")),
+                    });
+                }
+                c => string.push(c),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, JsonError> {
+        self.expect("[")?;
+        let mut values = vec![];
+        self.whitespace();
+        if self.peek() == Some(']') {
+            self.position += 1;
+            return Ok(Json::Array(values));
+        }
+        loop {
+            values.push(self.value()?);
+            self.whitespace();
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some(']') => {
+                    self.position += 1;
+                    return Ok(Json::Array(values));
+                }
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, JsonError> {
+        self.expect("{")?;
+        let mut fields = vec![];
+        self.whitespace();
+        if self.peek() == Some('}') {
+            self.position += 1;
+            return Ok(Json::Object(fields));
+        }
+        loop {
+            self.whitespace();
+            let key = self.string()?;
+            self.whitespace();
+            self.expect(":")?;
+            fields.push((key, self.value()?));
+            self.whitespace();
+            match self.peek() {
+                Some(',') => self.position += 1,
+                Some('}') => {
+                    self.position += 1;
+                    return Ok(Json::Object(fields));
+                }
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Json {
+        Json::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(json: &Json) -> Result<Self, JsonError> {
+        json.as_array()?.iter().map(T::from_json).collect()
+    }
+}
+
+impl ToJson for Tile {
+    fn to_json(&self) -> Json {
+        Json::String(self.to_string())
+    }
+}
+
+impl FromJson for Tile {
+    fn from_json(json: &Json) -> Result<Self, JsonError> {
+        json.as_str()?
+            .parse()
+            .map_err(|error| JsonError(format!("{}", error)))
+    }
+}
+
+impl ToJson for Wind {
+    fn to_json(&self) -> Json {
+        Json::String(wind_name(*self).to_string())
+    }
+}
+
+impl FromJson for Wind {
+    fn from_json(json: &Json) -> Result<Self, JsonError> {
+        json.as_str()?
+            .parse()
+            .map_err(|error| JsonError(format!("{}", error)))
+    }
+}
+
+impl ToJson for Yaku {
+    fn to_json(&self) -> Json {
+        Json::String(yaku_name(*self))
+    }
+}
+
+impl FromJson for Yaku {
+    fn from_json(json: &Json) -> Result<Self, JsonError> {
+        let name = json.as_str()?;
+        Yaku::all()
+            .into_iter()
+            .find(|yaku| yaku_name(*yaku) == name)
+            .ok_or_else(|| JsonError(format!("unknown yaku `{}`", name)))
+    }
+}
+
+fn wind_name(wind: Wind) -> &'static str {
+    match wind {
+        Wind::East => "East",
+        Wind::South => "South",
+        Wind::West => "West",
+        Wind::North => "North",
+    }
+}
+
+fn dragon_name(dragon: Dragon) -> &'static str {
+    match dragon {
+        Dragon::Green => "Green",
+        Dragon::Red => "Red",
+        Dragon::White => "White",
+    }
+}
+
+/// the name a yaku is written as. these are part of the format and don't
+/// follow renames of the variants.
+fn yaku_name(yaku: Yaku) -> String {
+    match yaku {
+        Yaku::SeatWind(wind) => format!("SeatWind({})", wind_name(wind)),
+        Yaku::PrevalentWind(wind) => {
+            format!("PrevalentWind({})", wind_name(wind))
+        }
+        Yaku::Dragons(dragon) => format!("Dragons({})", dragon_name(dragon)),
+        Yaku::Riichi => "Riichi".to_string(),
+        Yaku::AllSimples => "AllSimples".to_string(),
+        Yaku::FullyConcealedHand => "FullyConcealedHand".to_string(),
+        Yaku::Pinfu => "Pinfu".to_string(),
+        Yaku::PureDoubleSequence => "PureDoubleSequence".to_string(),
+        Yaku::RobbingAKan => "RobbingAKan".to_string(),
+        Yaku::AfterAKan => "AfterAKan".to_string(),
+        Yaku::UnderTheSea => "UnderTheSea".to_string(),
+        Yaku::UnderTheRiver => "UnderTheRiver".to_string(),
+        Yaku::Ippatsu => "Ippatsu".to_string(),
+        Yaku::TsubameGaeshi => "TsubameGaeshi".to_string(),
+        Yaku::Kanburi => "Kanburi".to_string(),
+        Yaku::Shiiatutaotai => "Shiiatutaotai".to_string(),
+        Yaku::DoubeRiichi => "DoubleRiichi".to_string(),
+        Yaku::TripleTriplets => "TripleTriplets".to_string(),
+        Yaku::ThreeQuads => "ThreeQuads".to_string(),
+        Yaku::AllTriplets => "AllTriplets".to_string(),
+        Yaku::ThreeConcealedTriplets => "ThreeConcealedTriplets".to_string(),
+        Yaku::LittleThreeDragons => "LittleThreeDragons".to_string(),
+        Yaku::AllTerminalsAndHonors => "AllTerminalsAndHonors".to_string(),
+        Yaku::SevenPairs => "SevenPairs".to_string(),
+        Yaku::HalfOutsideHand => "HalfOutsideHand".to_string(),
+        Yaku::PureStraight => "PureStraight".to_string(),
+        Yaku::MixedTripleSequence => "MixedTripleSequence".to_string(),
+        Yaku::Uumensai => "Uumensai".to_string(),
+        Yaku::ThreeChainedTriplets => "ThreeChainedTriplets".to_string(),
+        Yaku::TwicePureDoubleSequence => "TwicePureDoubleSequence".to_string(),
+        Yaku::FullyOutsideHand => "FullyOutsideHand".to_string(),
+        Yaku::HalfFlush => "HalfFlush".to_string(),
+        Yaku::PureTripleChow => "PureTripleChow".to_string(),
+        Yaku::FullFlush => "FullFlush".to_string(),
+        Yaku::ManganAtDraw => "ManganAtDraw".to_string(),
+        Yaku::NagashiMangan => "NagashiMangan".to_string(),
+        Yaku::Iipinmoyue => "Iipinmoyue".to_string(),
+        Yaku::Chuupinraoyui => "Chuupinraoyui".to_string(),
+        Yaku::BlessingOfHeaven => "BlessingOfHeaven".to_string(),
+        Yaku::BlessingOfEarth => "BlessingOfEarth".to_string(),
+        Yaku::BigThreeDragons => "BigThreeDragons".to_string(),
+        Yaku::FourConcealedTriplets => "FourConcealedTriplets".to_string(),
+        Yaku::AllHonors => "AllHonors".to_string(),
+        Yaku::AllGreen => "AllGreen".to_string(),
+        Yaku::AllTerminals => "AllTerminals".to_string(),
+        Yaku::ThirteenOrphans => "ThirteenOrphans".to_string(),
+        Yaku::FourLittleWinds => "FourLittleWinds".to_string(),
+        Yaku::FourQuads => "FourQuads".to_string(),
+        Yaku::NineGates => "NineGates".to_string(),
+        Yaku::HandOfMan => "HandOfMan".to_string(),
+        Yaku::BigWheels => "BigWheels".to_string(),
+        Yaku::BambooForest => "BambooForest".to_string(),
+        Yaku::NumerousNeighbours => "NumerousNeighbours".to_string(),
+        Yaku::Ishinouenimosannen => "Ishinouenimosannen".to_string(),
+        Yaku::SingleWaitFourConcealedTriplets => {
+            "SingleWaitFourConcealedTriplets".to_string()
+        }
+        Yaku::ThirteenWaitThirteenOrphans => {
+            "ThirteenWaitThirteenOrphans".to_string()
+        }
+        Yaku::TrueNineGates => "TrueNineGates".to_string(),
+        Yaku::FourBigWinds => "FourBigWinds".to_string(),
+        Yaku::BigSevenStars => "BigSevenStars".to_string(),
+        Yaku::SeatFlower => "SeatFlower".to_string(),
+        Yaku::SeatSeason => "SeatSeason".to_string(),
+        Yaku::AllFlowers => "AllFlowers".to_string(),
+        Yaku::AllSeasons => "AllSeasons".to_string(),
+    }
+}
+
+impl ToJson for Player {
+    fn to_json(&self) -> Json {
+        Json::Object(vec![
+            ("seat".to_string(), self.seat.to_json()),
+            ("hand".to_string(), self.hand.to_json()),
+            ("dealt_in".to_string(), self.dealt_in.to_json()),
+            ("discarded".to_string(), self.discarded.to_json()),
+            ("called".to_string(), self.called.to_json()),
+            ("passed".to_string(), self.passed.to_json()),
+            ("kan".to_string(), self.kan.to_json()),
+            ("bonus".to_string(), self.bonus.to_json()),
+            ("riichi".to_string(), Json::Bool(self.riichi)),
+        ])
+    }
+}
+
+impl FromJson for Player {
+    fn from_json(json: &Json) -> Result<Self, JsonError> {
+        Ok(Player {
+            seat: Wind::from_json(json.get("seat")?)?,
+            hand: Vec::from_json(json.get("hand")?)?,
+            dealt_in: Vec::from_json(json.get("dealt_in")?)?,
+            discarded: Vec::from_json(json.get("discarded")?)?,
+            called: Vec::from_json(json.get("called")?)?,
+            passed: Vec::from_json(json.get("passed")?)?,
+            kan: Vec::from_json(json.get("kan")?)?,
+            bonus: Vec::from_json(json.get("bonus")?)?,
+            riichi: json.get("riichi")?.as_bool()?,
+        })
+    }
+}
+
+impl ToJson for WinContext {
+    fn to_json(&self) -> Json {
+        Json::Object(vec![
+            ("prevalent_wind".to_string(), self.prevalent_wind.to_json()),
+            ("tsumo".to_string(), Json::Bool(self.tsumo)),
+            ("last_tile".to_string(), Json::Bool(self.last_tile)),
+            ("after_kan".to_string(), Json::Bool(self.after_kan)),
+            (
+                "winning_tile".to_string(),
+                match self.winning_tile {
+                    Some(tile) => tile.to_json(),
+                    None => Json::Null,
+                },
+            ),
+        ])
+    }
+}
+
+impl FromJson for WinContext {
+    /// only `prevalent_wind` is required, the flags default to false and
+    /// `winning_tile` to unknown.
+    fn from_json(json: &Json) -> Result<Self, JsonError> {
+        let flag = |key| match json.get(key) {
+            Ok(value) => value.as_bool(),
+            Err(_) => Ok(false),
+        };
+        Ok(WinContext {
+            prevalent_wind: Wind::from_json(json.get("prevalent_wind")?)?,
+            tsumo: flag("tsumo")?,
+            last_tile: flag("last_tile")?,
+            after_kan: flag("after_kan")?,
+            winning_tile: match json.get("winning_tile") {
+                Ok(Json::Null) | Err(_) => None,
+                Ok(tile) => Some(Tile::from_json(tile)?),
+            },
+        })
+    }
+}
+
+impl ToJson for usize {
+    fn to_json(&self) -> Json {
+        Json::Number(*self as f64)
+    }
+}
+
+impl FromJson for usize {
+    fn from_json(json: &Json) -> Result<Self, JsonError> {
+        usize::try_from(json.as_i64()?)
+            .map_err(|_| JsonError("expected a positive integer".to_string()))
+    }
+}
+
+impl ToJson for i32 {
+    fn to_json(&self) -> Json {
+        Json::Number(*self as f64)
+    }
+}
+
+impl FromJson for i32 {
+    fn from_json(json: &Json) -> Result<Self, JsonError> {
+        i32::try_from(json.as_i64()?)
+            .map_err(|_| JsonError("integer out of range".to_string()))
+    }
+}
+
+fn limit_name(limit: Limit) -> String {
+    match limit {
+        Limit::Mangan => "Mangan".to_string(),
+        Limit::Haneman => "Haneman".to_string(),
+        Limit::Baiman => "Baiman".to_string(),
+        Limit::Sanbaiman => "Sanbaiman".to_string(),
+        Limit::Yakuman(count) => format!("Yakuman({})", count),
+    }
+}
+
+impl ToJson for Score {
+    /// `limit` and `base_points` follow from the han and fu and are only
+    /// written for readers that don't score hands themselves.
+    fn to_json(&self) -> Json {
+        Json::Object(vec![
+            ("han".to_string(), Json::Number(self.han as f64)),
+            ("fu".to_string(), Json::Number(self.fu as f64)),
+            (
+                "limit".to_string(),
+                match self.limit() {
+                    Some(limit) => Json::String(limit_name(limit)),
+                    None => Json::Null,
+                },
+            ),
+            (
+                "base_points".to_string(),
+                Json::Number(self.base_points() as f64),
+            ),
+        ])
+    }
+}
+
+impl FromJson for Score {
+    fn from_json(json: &Json) -> Result<Self, JsonError> {
+        let number = |key| {
+            u8::try_from(json.get(key)?.as_i64()?)
+                .map_err(|_| JsonError(format!("`{}` out of range", key)))
+        };
+        Ok(Score::new(number("han")?, number("fu")?))
+    }
+}
+
+impl ToJson for TsumoPayment {
+    fn to_json(&self) -> Json {
+        Json::Object(vec![
+            ("dealer".to_string(), Json::Number(self.dealer as f64)),
+            (
+                "non_dealer".to_string(),
+                Json::Number(self.non_dealer as f64),
+            ),
+        ])
+    }
+}
+
+impl FromJson for TsumoPayment {
+    fn from_json(json: &Json) -> Result<Self, JsonError> {
+        let number = |key| {
+            u32::try_from(json.get(key)?.as_i64()?)
+                .map_err(|_| JsonError(format!("`{}` out of range", key)))
+        };
+        Ok(TsumoPayment {
+            dealer: number("dealer")?,
+            non_dealer: number("non_dealer")?,
+        })
+    }
+}
+
+impl ToJson for Win {
+    fn to_json(&self) -> Json {
+        Json::Object(vec![
+            ("winner".to_string(), self.winner.to_json()),
+            (
+                "discarder".to_string(),
+                match self.discarder {
+                    Some(discarder) => discarder.to_json(),
+                    None => Json::Null,
+                },
+            ),
+            ("yakus".to_string(), self.yakus.to_json()),
+            ("score".to_string(), self.score.to_json()),
+        ])
+    }
+}
+
+impl FromJson for Win {
+    fn from_json(json: &Json) -> Result<Self, JsonError> {
+        Ok(Win {
+            winner: usize::from_json(json.get("winner")?)?,
+            discarder: match json.get("discarder") {
+                Ok(Json::Null) | Err(_) => None,
+                Ok(discarder) => Some(usize::from_json(discarder)?),
+            },
+            yakus: Vec::from_json(json.get("yakus")?)?,
+            score: Score::from_json(json.get("score")?)?,
+        })
+    }
+}
+
+fn abortive_draw_name(draw: AbortiveDraw) -> &'static str {
+    match draw {
+        AbortiveDraw::NineTerminals => "NineTerminals",
+        AbortiveDraw::FourWinds => "FourWinds",
+        AbortiveDraw::FourRiichi => "FourRiichi",
+        AbortiveDraw::FourKans => "FourKans",
+        AbortiveDraw::TripleRon => "TripleRon",
+    }
+}
+
+impl ToJson for RoundEnd {
+    /// an object with a `type` of `Win`, `ExhaustiveDraw` or
+    /// `AbortiveDraw` and the fields of that ending.
+    fn to_json(&self) -> Json {
+        let mut fields = vec![];
+        let kind = match self {
+            RoundEnd::Win(wins) => {
+                fields.push(("wins".to_string(), wins.to_json()));
+                "Win"
+            }
+            RoundEnd::ExhaustiveDraw { tenpai, nagashi } => {
+                fields.push(("tenpai".to_string(), tenpai.to_json()));
+                fields.push(("nagashi".to_string(), nagashi.to_json()));
+                "ExhaustiveDraw"
+            }
+            RoundEnd::AbortiveDraw(draw) => {
+                fields.push((
+                    "reason".to_string(),
+                    Json::String(abortive_draw_name(*draw).to_string()),
+                ));
+                "AbortiveDraw"
+            }
+        };
+        fields.insert(0, ("type".to_string(), Json::String(kind.to_string())));
+        Json::Object(fields)
+    }
+}
+
+impl FromJson for RoundEnd {
+    fn from_json(json: &Json) -> Result<Self, JsonError> {
+        match json.get("type")?.as_str()? {
+            "Win" => Ok(RoundEnd::Win(Vec::from_json(json.get("wins")?)?)),
+            "ExhaustiveDraw" => Ok(RoundEnd::ExhaustiveDraw {
+                tenpai: Vec::from_json(json.get("tenpai")?)?,
+                nagashi: Vec::from_json(json.get("nagashi")?)?,
+            }),
+            "AbortiveDraw" => {
+                let reason = json.get("reason")?.as_str()?;
+                [
+                    AbortiveDraw::NineTerminals,
+                    AbortiveDraw::FourWinds,
+                    AbortiveDraw::FourRiichi,
+                    AbortiveDraw::FourKans,
+                    AbortiveDraw::TripleRon,
+                ]
+                .into_iter()
+                .find(|draw| abortive_draw_name(*draw) == reason)
+                .map(RoundEnd::AbortiveDraw)
+                .ok_or_else(|| {
+                    JsonError(format!("unknown abortive draw `{}`", reason))
+                })
+            }
+            kind => Err(JsonError(format!("unknown round end `{}`", kind))),
+        }
+    }
+}
+
+impl ToJson for RoundResult {
+    fn to_json(&self) -> Json {
+        Json::Object(vec![
+            ("end".to_string(), self.end.to_json()),
+            ("payments".to_string(), self.payments.to_json()),
+            ("dealer_keeps".to_string(), Json::Bool(self.dealer_keeps)),
+        ])
+    }
+}
+
+impl FromJson for RoundResult {
+    fn from_json(json: &Json) -> Result<Self, JsonError> {
+        Ok(RoundResult {
+            end: RoundEnd::from_json(json.get("end")?)?,
+            payments: Vec::from_json(json.get("payments")?)?,
+            dealer_keeps: json.get("dealer_keeps")?.as_bool()?,
+        })
+    }
+}
+
+/// an evaluation request: the player next to the fields of their
+/// `WinContext`. the player's hand and melds must add up to a complete
+/// hand, a quad counting as three tiles.
+pub fn evaluation_from_json(
+    json: &Json,
+) -> Result<(Player, WinContext), JsonError> {
+    let player = Player::from_json(json.get("player")?)?;
+    if player.hand.len() + 3 * player.melds() != 14 {
+        return Err(JsonError(
+            "`hand` and the melds must make 14 tiles".to_string(),
+        ));
+    }
+    Ok((player, WinContext::from_json(json)?))
+}
+
+/// a view of the table: the player, the opponents in turn order after them
+/// and the dora indicators, which may be left out.
+pub fn table_from_json(
+    json: &Json,
+) -> Result<(Player, Vec<Player>, Vec<Tile>), JsonError> {
+    let player = Player::from_json(json.get("player")?)?;
+    let opponents = Vec::from_json(json.get("opponents")?)?;
+    let dora_indicators = match json.get("dora_indicators") {
+        Ok(indicators) => Vec::from_json(indicators)?,
+        Err(_) => vec![],
+    };
+    Ok((player, opponents, dora_indicators))
+}
+
+/// the result of an evaluation, echoing the context it was evaluated with.
+/// the score is left null when it can't be worked out.
+pub fn evaluation_to_json(
+    context: &WinContext,
+    yakus: &[Yaku],
+    score: Option<Score>,
+) -> Json {
+    Json::Object(vec![
+        ("context".to_string(), context.to_json()),
+        ("yakus".to_string(), yakus.to_vec().to_json()),
+        (
+            "score".to_string(),
+            match score {
+                Some(score) => score.to_json(),
+                None => Json::Null,
+            },
+        ),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        json::{evaluation_from_json, FromJson, Json, ToJson},
+        player::Player,
+        round::{AbortiveDraw, RoundEnd, RoundResult, Win},
+        score::{Score, TsumoPayment},
+        tile::{Dragon, Simple, Tile, Wind},
+        yaku::Yaku,
+    };
+
+    #[test]
+    fn parse() {
+        let json: Json = r#" {"a": [1, -2.5e1, true, null], "b\n": "A\"" } "#
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            json,
+            Json::Object(vec![
+                (
+                    "a".to_string(),
+                    Json::Array(vec![
+                        Json::Number(1.0),
+                        Json::Number(-25.0),
+                        Json::Bool(true),
+                        Json::Null,
+                    ])
+                ),
+                ("b\n".to_string(), Json::String("A\"".to_string())),
+            ])
+        );
+        assert_eq!(json.to_string().parse::<Json>().unwrap(), json);
+
+        assert!("[1, 2".parse::<Json>().is_err());
+        assert!("{\"a\" 1}".parse::<Json>().is_err());
+        assert!("[] []".parse::<Json>().is_err());
+    }
+
+    #[test]
+    fn player_round_trip() {
+        let player = Player {
+            dealt_in: vec![Tile::Simple(Simple::Sou(5)); 3],
+            discarded: vec![Tile::Dragon(Dragon::Red)],
+            riichi: true,
+            ..Player::new(
+                Wind::South,
+                vec![
+                    Tile::Simple(Simple::Man(1)),
+                    Tile::Simple(Simple::Pin(9)),
+                    Tile::Dragon(Dragon::White),
+                    Tile::Wind(Wind::North),
+                ],
+            )
+        };
+
+        let text = player.to_json().to_string();
+        assert_eq!(
+            text,
+            r#"{"seat":"South","hand":["M1","P9","WD","NW"],"dealt_in":["S5","S5","S5"],"discarded":["RD"],"called":[],"passed":[],"kan":[],"bonus":[],"riichi":true}"#
+        );
+
+        let parsed = Player::from_json(&text.parse().unwrap()).unwrap();
+        assert!(parsed.seat == player.seat);
+        assert!(parsed.hand == player.hand);
+        assert!(parsed.dealt_in == player.dealt_in);
+        assert!(parsed.discarded == player.discarded);
+        assert!(parsed.riichi);
+
+        // every list is required, empty or not.
+        let missing = text.replace(r#""called":[],"#, "");
+        assert!(Player::from_json(&missing.parse().unwrap()).is_err());
+    }
+
+    #[test]
+    fn yaku_round_trip() {
+        for yaku in Yaku::all() {
+            assert_eq!(Yaku::from_json(&yaku.to_json()), Ok(yaku));
+        }
+        assert!(Yaku::from_json(&Json::String("Tenhou".to_string())).is_err());
+        assert_eq!(
+            Yaku::DoubeRiichi.to_json(),
+            Json::String("DoubleRiichi".to_string())
+        );
+        assert_eq!(
+            Yaku::SeatWind(Wind::East).to_json(),
+            Json::String("SeatWind(East)".to_string())
+        );
+    }
+
+    #[test]
+    fn evaluation_with_kans() {
+        let round_trip = |player: &str| {
+            let text = format!(
+                r#"{{"player":{},"prevalent_wind":"East","tsumo":true}}"#,
+                player
+            );
+            let (player, context) =
+                evaluation_from_json(&text.parse().unwrap()).unwrap();
+            let mut json = context.to_json();
+            if let Json::Object(fields) = &mut json {
+                fields.push(("player".to_string(), player.to_json()));
+            }
+            let (again, _) = evaluation_from_json(&json).unwrap();
+            assert!(again.hand == player.hand);
+            assert!(again.dealt_in == player.dealt_in);
+            assert!(again.kan == player.kan);
+            player.melds()
+        };
+
+        // a concealed kan of red dragons next to 11 tiles.
+        let concealed = r#"{"seat":"East","hand":["M1","M2","M3","P4","P5","P6","S7","S8","S9","M5","M5"],"dealt_in":[],"discarded":[],"called":[],"passed":[],"kan":["RD"],"bonus":[],"riichi":false}"#;
+        assert_eq!(round_trip(concealed), 1);
+        // an open one keeps three of its tiles in `dealt_in`.
+        let open = r#"{"seat":"East","hand":["M1","M2","M3","P4","P5","P6","S7","S8","S9","M5","M5"],"dealt_in":["RD","RD","RD"],"discarded":[],"called":[],"passed":[],"kan":["RD"],"bonus":[],"riichi":false}"#;
+        assert_eq!(round_trip(open), 1);
+
+        let missing = concealed.replace(r#""kan":["RD"]"#, r#""kan":[]"#);
+        let text =
+            format!(r#"{{"player":{},"prevalent_wind":"East"}}"#, missing);
+        assert!(evaluation_from_json(&text.parse().unwrap()).is_err());
+    }
+
+    #[test]
+    fn result_round_trip() {
+        let result = RoundResult {
+            end: RoundEnd::Win(vec![Win {
+                winner: 2,
+                discarder: Some(0),
+                yakus: vec![Yaku::Riichi, Yaku::Dragons(Dragon::Red)],
+                score: Score::new(5, 40),
+            }]),
+            payments: vec![-8300, 0, 9300, 0],
+            dealer_keeps: false,
+        };
+
+        let text = result.to_json().to_string();
+        assert_eq!(
+            text,
+            r#"{"end":{"type":"Win","wins":[{"winner":2,"discarder":0,"yakus":["Riichi","Dragons(Red)"],"score":{"han":5,"fu":40,"limit":"Mangan","base_points":2000}}]},"payments":[-8300,0,9300,0],"dealer_keeps":false}"#
+        );
+        assert_eq!(RoundResult::from_json(&text.parse().unwrap()), Ok(result));
+
+        let draw = RoundEnd::AbortiveDraw(AbortiveDraw::FourKans);
+        assert_eq!(RoundEnd::from_json(&draw.to_json()), Ok(draw));
+        let payment = TsumoPayment {
+            dealer: 2000,
+            non_dealer: 1000,
+        };
+        assert_eq!(TsumoPayment::from_json(&payment.to_json()), Ok(payment));
+    }
+}
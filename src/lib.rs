@@ -0,0 +1,24 @@
+pub mod call;
+pub mod decompose;
+pub mod defense;
+pub mod efficiency;
+pub mod event;
+pub mod furiten;
+pub mod game;
+pub mod history;
+pub mod json;
+pub mod lookup;
+pub mod player;
+pub mod potential;
+pub mod riichi;
+pub mod rng;
+pub mod round;
+pub mod ruleset;
+pub mod score;
+pub mod sha256;
+pub mod shanten;
+pub mod simulation;
+pub mod tile;
+pub mod wait;
+pub mod wall;
+pub mod yaku;
@@ -0,0 +1,261 @@
+use std::sync::OnceLock;
+
+use crate::{
+    shanten::{
+        self, combine, seven_pairs_shanten, shanten_of_blocks,
+        thirteen_orphans_shanten, Blocks,
+    },
+    tile::TILE_KINDS,
+};
+
+/// patterns with more tiles than a concealed hand can hold, or more than
+/// the four copies of a tile, aren't in the tables and fall back to
+/// `shanten::suit_blocks`.
+const MAX_TILES: u8 = 14;
+
+/// the blocks of every pattern of a numbered suit, indexed by its counts
+/// read as a base 5 number, packed by `pack`.
+static SUITS: OnceLock<Vec<u32>> = OnceLock::new();
+/// the same for the seven honors, which can't form sequences.
+static HONORS: OnceLock<Vec<u32>> = OnceLock::new();
+
+/// the blocks of a numbered suit, see `shanten::suit_blocks`. the first
+/// call builds the table, every later one is a single lookup.
+pub fn suit_blocks(counts: &[u8; 9]) -> Blocks {
+    lookup(&SUITS, counts, 9, true)
+}
+
+/// the blocks of the honors, the first seven entries of `counts`.
+pub fn honor_blocks(counts: &[u8; 9]) -> Blocks {
+    lookup(&HONORS, counts, 7, false)
+}
+
+/// `shanten::standard_shanten` with one table lookup per suit.
+pub fn standard_shanten(counts: &[u8; TILE_KINDS], melds: usize) -> i8 {
+    shanten_of_blocks(&hand_blocks(counts), melds)
+}
+
+/// `shanten::shanten_of_counts` with one table lookup per suit.
+pub fn shanten(counts: &[u8; TILE_KINDS], melds: usize) -> i8 {
+    let standard = standard_shanten(counts, melds);
+    if melds > 0 {
+        return standard;
+    }
+    standard
+        .min(seven_pairs_shanten(counts))
+        .min(thirteen_orphans_shanten(counts))
+}
+
+/// whether the concealed tiles complete a hand with `melds` called sets,
+/// as four sets and a pair, seven pairs or thirteen orphans.
+pub fn is_complete(counts: &[u8; TILE_KINDS], melds: usize) -> bool {
+    shanten(counts, melds) == -1
+}
+
+/// builds both tables now rather than on first use, e.g. before timing
+/// anything.
+pub fn warm_up() {
+    suit_blocks(&[0; 9]);
+    honor_blocks(&[0; 9]);
+}
+
+fn hand_blocks(counts: &[u8; TILE_KINDS]) -> Blocks {
+    let mut combined = [[-1; 5]; 2];
+    combined[0][0] = 0;
+    for start in [0, 9, 18] {
+        let mut suit = [0; 9];
+        suit.copy_from_slice(&counts[start..start + 9]);
+        combined = combine(&combined, &suit_blocks(&suit));
+    }
+    let mut honors = [0; 9];
+    honors[..7].copy_from_slice(&counts[27..]);
+    combine(&combined, &honor_blocks(&honors))
+}
+
+fn lookup(
+    table: &OnceLock<Vec<u32>>,
+    counts: &[u8; 9],
+    kinds: usize,
+    sequences: bool,
+) -> Blocks {
+    if counts.iter().sum::<u8>() > MAX_TILES
+        || counts.iter().any(|count| *count > 4)
+    {
+        return shanten::suit_blocks(counts, sequences);
+    }
+    let table = table.get_or_init(|| build(kinds, sequences));
+    unpack(table[key(&counts[..kinds])])
+}
+
+fn key(counts: &[u8]) -> usize {
+    counts
+        .iter()
+        .rev()
+        .fold(0, |key, count| key * 5 + *count as usize)
+}
+
+/// fills in the patterns in key order. every way of taking a block off
+/// the lowest tile of a pattern leaves one with a smaller key, so its blocks
+/// are already known and the pattern's follow from them the way
+/// `shanten::suit_blocks` searches them.
+fn build(kinds: usize, sequences: bool) -> Vec<u32> {
+    let mut table = vec![0; 5usize.pow(kinds as u32)];
+    let mut empty = [[-1; 5]; 2];
+    empty[0][0] = 0;
+    table[0] = pack(&empty);
+
+    for pattern in 1..table.len() {
+        let mut counts = [0; 9];
+        let mut rest = pattern;
+        for count in counts.iter_mut().take(kinds) {
+            *count = (rest % 5) as u8;
+            rest /= 5;
+        }
+        if counts.iter().sum::<u8>() > MAX_TILES {
+            continue;
+        }
+
+        let index = counts.iter().position(|count| *count > 0).unwrap_or(0);
+        let sequence = sequences && index < 7;
+        let adjacent = sequences && index < 8;
+        let mut blocks = [[-1; 5]; 2];
+        let mut take = |offsets: &[(usize, u8)], block: Block| {
+            let mut rest = pattern;
+            for (offset, count) in offsets {
+                if counts[index + offset] < *count {
+                    return;
+                }
+                rest -= *count as usize * 5usize.pow((index + offset) as u32);
+            }
+            extend(&mut blocks, &unpack(table[rest]), block);
+        };
+
+        take(&[(0, 3)], Block::Set);
+        if sequence {
+            take(&[(0, 1), (1, 1), (2, 1)], Block::Set);
+        }
+        take(&[(0, 2)], Block::Pair);
+        take(&[(0, 2)], Block::Taatsu);
+        if adjacent {
+            take(&[(0, 1), (1, 1)], Block::Taatsu);
+        }
+        if sequence {
+            take(&[(0, 1), (2, 1)], Block::Taatsu);
+        }
+        take(&[(0, 1)], Block::Isolated);
+
+        table[pattern] = pack(&blocks);
+    }
+    table
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Block {
+    Set,
+    Pair,
+    Taatsu,
+    Isolated,
+}
+
+/// adds the blocks of `rest` with one more `block` to `blocks`.
+fn extend(blocks: &mut Blocks, rest: &Blocks, block: Block) {
+    for (pair, taatsu_by_sets) in rest.iter().enumerate() {
+        for (sets, taatsu) in taatsu_by_sets.iter().copied().enumerate() {
+            if taatsu < 0 {
+                continue;
+            }
+            let (pair, sets, taatsu) = match block {
+                Block::Set => (pair, (sets + 1).min(4), taatsu),
+                Block::Pair if pair == 0 => (1, sets, taatsu),
+                Block::Pair => continue,
+                Block::Taatsu => (pair, sets, (taatsu + 1).min(4)),
+                Block::Isolated => (pair, sets, taatsu),
+            };
+            let entry = &mut blocks[pair][sets];
+            *entry = (*entry).max(taatsu);
+        }
+    }
+}
+
+/// three bits per entry, each stored plus one so -1 fits.
+fn pack(blocks: &Blocks) -> u32 {
+    blocks
+        .iter()
+        .flatten()
+        .enumerate()
+        .fold(0, |packed, (index, taatsu)| {
+            packed | ((*taatsu + 1) as u32) << (3 * index)
+        })
+}
+
+fn unpack(packed: u32) -> Blocks {
+    let mut blocks = [[0; 5]; 2];
+    for (index, taatsu) in blocks.iter_mut().flatten().enumerate() {
+        *taatsu = ((packed >> (3 * index)) & 0b111) as i8 - 1;
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lookup,
+        rng::Rng,
+        ruleset::Ruleset,
+        shanten,
+        tile::{Tile, TILE_KINDS},
+    };
+
+    #[test]
+    fn matches_search() {
+        let mut rng = Rng::new(7);
+        let mut tiles = Ruleset::riichi().tiles();
+        for _ in 0..2000 {
+            rng.shuffle(&mut tiles);
+            for (size, melds) in [(14, 0), (13, 0), (11, 1), (5, 3)] {
+                let counts = Tile::counts(&tiles[..size]);
+                assert_eq!(
+                    lookup::shanten(&counts, melds),
+                    shanten::shanten_of_counts(&counts, melds)
+                );
+            }
+        }
+
+        for _ in 0..2000 {
+            let mut suit = [0; 9];
+            for count in suit.iter_mut() {
+                *count = rng.below(4) as u8;
+            }
+            if suit.iter().sum::<u8>() <= 14 {
+                assert_eq!(
+                    lookup::suit_blocks(&suit),
+                    shanten::suit_blocks(&suit, true)
+                );
+            }
+        }
+
+        // a flush hand, the most tiles a single suit pattern holds.
+        let mut counts = [0; TILE_KINDS];
+        counts[..9].copy_from_slice(&[3, 1, 1, 1, 1, 1, 1, 1, 4]);
+        assert!(lookup::is_complete(&counts, 0));
+        counts[8] = 3;
+        assert!(!lookup::is_complete(&counts, 0));
+        assert_eq!(lookup::shanten(&counts, 0), 0);
+
+        // five copies of a tile, which only typed in hands have, don't fit
+        // the base 5 keys.
+        let mut counts = [0; TILE_KINDS];
+        counts[8] = 5;
+        counts[9..15].fill(1);
+        counts[18] = 3;
+        assert_eq!(
+            lookup::shanten(&counts, 0),
+            shanten::shanten_of_counts(&counts, 0)
+        );
+        let suit = [5, 0, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(
+            lookup::suit_blocks(&suit),
+            shanten::suit_blocks(&suit, true)
+        );
+    }
+}
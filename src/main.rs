@@ -1,19 +1,434 @@
-use std::io;
+use std::{io, sync::mpsc::Receiver};
 
-use crate::{
+use mahjong_cli::{
+    call::Call,
+    event::GameEvent,
+    furiten::FuritenTracker,
+    game::Game,
+    history::{Action, Change, History},
+    json::{
+        evaluation_from_json, evaluation_to_json, table_from_json, FromJson,
+        Json,
+    },
     player::Player,
-    tile::{Dragon, Tile, Wind},
+    potential::han_range,
+    riichi::Situation,
+    rng::{entropy, Rng},
+    round::{Claim, Phase, Round, RoundEnd},
+    ruleset::{GameLength, Ruleset},
+    score::{fu, Score},
+    simulation::Simulation,
+    tile::{Tile, Wind},
+    wall::{salt, Wall},
 };
 
-pub mod player;
-pub mod tile;
-pub mod yaku;
+fn read_line() -> String {
+    let mut input = String::new();
+    io::stdin()
+        .read_line(&mut input)
+        .expect("Failed to read line");
+    input
+}
+
+/// asks for a seed, taking an unpredictable one when none is given. none
+/// when the input isn't a number.
+fn read_seed() -> Option<u64> {
+    println!("seed (empty for a random one):");
+    match read_line().trim() {
+        "" => Some(entropy()),
+        seed => match seed.parse() {
+            Ok(seed) => Some(seed),
+            Err(_) => {
+                println!("the seed must be a number");
+                None
+            }
+        },
+    }
+}
+
+/// prints the hash of the wall under a fresh salt, which is returned to be
+/// revealed with the wall once it's played.
+fn commit_wall(wall: &Wall) -> String {
+    let salt = salt();
+    println!("wall hash:\n\t{}", wall.hash(&salt));
+    salt
+}
+
+fn reveal_wall(wall: &Wall, salt: &str) {
+    println!("wall:\n\t{}", wall);
+    println!("salt:\n\t{}", salt);
+}
+
+/// asks for the tiles of a concealed hand.
+fn read_tiles() -> Option<Vec<Tile>> {
+    println!("tiles of a concealed hand, e.g. M1 M2 M3 P5 GD ...:");
+    match read_line()
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<Vec<Tile>, _>>()
+    {
+        Ok(hand) if Tile::counts(&hand).iter().any(|count| *count > 4) => {
+            println!("there are only four copies of each tile");
+            None
+        }
+        Ok(hand) => Some(hand),
+        Err(error) => {
+            println!("{}", error);
+            None
+        }
+    }
+}
+
+/// everyone at the table but the player at `position`.
+fn others(round: &Round, position: usize) -> Vec<Player> {
+    round
+        .players
+        .iter()
+        .enumerate()
+        .filter(|(other, _)| *other != position)
+        .map(|(_, player)| player.clone())
+        .collect()
+}
+
+/// the best discard by `Player::recommend_discards`, for the players the
+/// computer plays.
+fn computer_discard(round: &Round) -> Tile {
+    let candidates = round.current().recommend_discards(
+        &others(round, round.turn),
+        &round.wall.dora_indicators(),
+        round.prevalent_wind,
+    );
+    let allowed = |tile: &Tile| !round.kuikae.contains(tile);
+    match candidates
+        .iter()
+        .map(|candidate| candidate.acceptance.discard)
+        .find(allowed)
+    {
+        Some(tile) => tile,
+        None => round
+            .current()
+            .hand
+            .iter()
+            .copied()
+            .find(allowed)
+            .unwrap_or(round.current().hand[0]),
+    }
+}
+
+/// any ron, otherwise the first call `Player::advise_calls` recommends.
+fn computer_claim(round: &Round, position: usize) -> Option<Claim> {
+    let claims = round.available_claims(position);
+    if claims.contains(&Claim::Ron) {
+        return Some(Claim::Ron);
+    }
+    let tile = round.discarded()?;
+    round.players[position]
+        .advise_calls(
+            tile,
+            true,
+            &others(round, position),
+            &round.wall.dora_indicators(),
+            round.prevalent_wind,
+            round.ruleset.kuikae,
+        )
+        .into_iter()
+        .map(|advice| (advice.recommended, Claim::Call(advice.call)))
+        .find(|(recommended, claim)| *recommended && claims.contains(claim))
+        .map(|(_, claim)| claim)
+}
+
+/// prints the moves made at the table since the last call, leaving out the
+/// draws, which stay hidden, and how the round ended, see `print_result`.
+fn print_events(round: &Round, events: &Receiver<GameEvent>) {
+    let seat = |position: usize| round.players[position].seat;
+    for event in events.try_iter() {
+        match event {
+            GameEvent::Discard { position, tile } => {
+                println!("{:?} discards {}", seat(position), tile)
+            }
+            GameEvent::Riichi { position, .. } => {
+                println!("{:?} declares riichi", seat(position))
+            }
+            GameEvent::Chi { caller, start, .. } => {
+                println!("{:?} calls {}", seat(caller), Call::Chi(start))
+            }
+            GameEvent::Pon { caller, tile, .. } => {
+                println!("{:?} calls pon on {}", seat(caller), tile)
+            }
+            GameEvent::Kan { position, tile, .. } => {
+                println!("{:?} declares a kan of {}", seat(position), tile)
+            }
+            GameEvent::Nukidora { position } => {
+                println!("{:?} sets a north aside", seat(position))
+            }
+            GameEvent::DoraReveal { indicator } => {
+                println!("new dora indicator {}", indicator)
+            }
+            GameEvent::Rewind { .. } => println!("moves taken back"),
+            _ => {}
+        }
+    }
+}
+
+/// whether the round waits on a discard from the player at position 0.
+fn is_decision(round: &Round) -> bool {
+    round.phase == Phase::Discard && round.turn == 0
+}
+
+/// steps back to the decision before the current one, or forward to the
+/// next one along the branch last played. false when there's none.
+fn step(history: &mut History<Round, Action>, back: bool) -> bool {
+    let mut node = history.position();
+    loop {
+        let current = history.node(node).unwrap();
+        let next = if back { current.parent } else { current.next() };
+        let Some(next) = next else {
+            return false;
+        };
+        node = next;
+        if is_decision(&history.node(node).unwrap().state) {
+            return history.go_to(node);
+        }
+    }
+}
+
+/// plays the round to its end, asking for the moves of the player at
+/// position 0 and letting the computer play the others. undo and redo step
+/// between the decisions of the player in this round, and a different move
+/// from there plays out a new branch on the same wall. none when the input
+/// ended.
+fn play_round(mut round: Round) -> Option<Round> {
+    let events = round.subscribe();
+    let mut history = History::new(round);
+    loop {
+        let round = history.current();
+        print_events(round, &events);
+        match round.phase {
+            Phase::Draw => {
+                let _ = history.act(Action::Draw);
+            }
+            Phase::Discard if round.turn != 0 => {
+                let riichi = round.current().riichi;
+                let tile = match round.drawn.filter(|_| riichi) {
+                    Some(tile) => tile,
+                    None => computer_discard(round),
+                };
+                if history.act(Action::Tsumo).is_ok()
+                    || history.act(Action::NineTerminals).is_ok()
+                    || history.act(Action::Nukidora).is_ok()
+                {
+                    continue;
+                }
+                if riichi || history.act(Action::Riichi(tile)).is_err() {
+                    let _ = history.act(Action::Discard(tile));
+                }
+            }
+            Phase::Discard => {
+                if let Some(drawn) = round.drawn {
+                    print!("drew {}, ", drawn);
+                }
+                print!("hand:\n\t");
+                for tile in &round.current().hand {
+                    print!("{} ", tile);
+                }
+                for tile in &round.current().dealt_in {
+                    print!("[{}] ", tile);
+                }
+                println!();
+                if round.can_declare_nine_terminals() {
+                    println!("nine terminals: abort to end the round");
+                }
+                if round.can_declare_nukidora() {
+                    println!("nuki to set a north aside");
+                }
+                loop {
+                    println!(
+                        "a tile to discard, riichi or kan and a tile, tsumo, or undo or redo:"
+                    );
+                    let input = read_line();
+                    if input.is_empty() {
+                        return None;
+                    }
+                    let mut words = input.split_whitespace();
+                    let (action, tile) = match (words.next(), words.next()) {
+                        (Some(action), Some(tile)) => (action, tile),
+                        (Some(tile), None) => ("discard", tile),
+                        _ => continue,
+                    };
+                    let action = match tile {
+                        "undo" | "redo" => {
+                            if !step(&mut history, tile == "undo") {
+                                println!("nothing to {}", tile);
+                                continue;
+                            }
+                            break;
+                        }
+                        "tsumo" => Action::Tsumo,
+                        "abort" => Action::NineTerminals,
+                        "nuki" => Action::Nukidora,
+                        _ => match tile.parse() {
+                            Ok(tile) => match action {
+                                "riichi" => Action::Riichi(tile),
+                                "kan" => Action::Kan(tile),
+                                _ => Action::Discard(tile),
+                            },
+                            Err(error) => {
+                                println!("{}", error);
+                                continue;
+                            }
+                        },
+                    };
+                    match history.act(action) {
+                        Ok(_) => break,
+                        Err(error) => println!("{}", error),
+                    }
+                }
+            }
+            Phase::Claims => {
+                for position in 1..history.current().players.len() {
+                    if let Some(claim) =
+                        computer_claim(history.current(), position)
+                    {
+                        let _ = history.act(Action::Claim(position, claim));
+                    }
+                }
+
+                let claims = history.current().available_claims(0);
+                if !claims.is_empty() {
+                    println!("claim the discard, or nothing to pass:");
+                    for (index, claim) in claims.iter().enumerate() {
+                        println!("\t{} for {}", index + 1, claim);
+                    }
+                    let input = read_line();
+                    if input.is_empty() {
+                        return None;
+                    }
+                    if let Some(claim) = input
+                        .trim()
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|index| claims.get(index.wrapping_sub(1)))
+                    {
+                        let _ = history.act(Action::Claim(0, *claim));
+                    }
+                }
+                let _ = history.act(Action::Resolve);
+            }
+            Phase::Ended => return Some(history.current().clone()),
+        }
+    }
+}
+
+/// prints how a round that was played out ended.
+fn print_result(round: &Round) {
+    match round.result.as_ref().map(|result| &result.end) {
+        Some(RoundEnd::Win(wins)) => {
+            for win in wins {
+                let yakus: Vec<String> =
+                    win.yakus.iter().map(|yaku| yaku.to_string()).collect();
+                println!(
+                    "{:?} wins with {}: {}",
+                    round.players[win.winner].seat,
+                    win.score,
+                    yakus.join(", ")
+                )
+            }
+        }
+        Some(RoundEnd::ExhaustiveDraw { tenpai, nagashi }) => {
+            println!("the wall ran out");
+            for win in nagashi {
+                println!(
+                    "{:?} has a nagashi mangan",
+                    round.players[win.winner].seat
+                );
+            }
+            for position in tenpai {
+                println!("{:?} is tenpai", round.players[*position].seat);
+            }
+        }
+        Some(RoundEnd::AbortiveDraw(draw)) => {
+            println!("abortive draw, {}", draw)
+        }
+        None => {}
+    }
+    println!("scores: {:?}", round.scores);
+}
+
+/// prints the shanten, waits or discards and the potential of a concealed
+/// hand, false when it has neither 13 nor 14 tiles.
+fn analyze(player: &Player) -> bool {
+    match player.hand.len() {
+        13 => {
+            println!("shanten:\n\t{}", player.shanten());
+            print!("waits:\n\t");
+            for wait in player.waits() {
+                print!("{} (", wait.tile);
+                for (index, shape) in wait.shapes.iter().enumerate() {
+                    if index > 0 {
+                        print!(", ");
+                    }
+                    print!("{}", shape);
+                }
+                if wait.impossible {
+                    print!(", impossible");
+                }
+                print!("), ");
+            }
+            println!();
+            if let Some(advice) =
+                player.advise_riichi(&[], &[], Wind::East, &Situation::new())
+            {
+                println!("riichi:\n\t{}", advice.explanation);
+            }
+        }
+        14 => {
+            println!("shanten:\n\t{}", player.shanten());
+            print!("yakus:\n\t");
+            for yaku in player.evaluate_yakus(Wind::East) {
+                print!("{}, ", yaku);
+            }
+            println!();
+            println!("discards:");
+            for candidate in player.recommend_discards(&[], &[], Wind::East) {
+                println!(
+                    "\t{}: {}",
+                    candidate.acceptance.discard, candidate.explanation
+                );
+            }
+        }
+        _ => {
+            println!("a concealed hand has 13 or 14 tiles");
+            return false;
+        }
+    }
+
+    let potentials = player.potential(Wind::East);
+    println!("potential:");
+    if let Some((low, high)) = han_range(&potentials) {
+        println!("\t{} to {} han from yakus", low, high);
+    }
+    for potential in potentials {
+        println!("\t{} {}", potential.yaku, potential.reach);
+    }
+    true
+}
 
 fn main() {
     loop {
         println!("options:");
         println!("\t1 for a randomly populated hand");
-        println!("\t2 to manually populate a hand");
+        println!(
+            "\t2 to manually populate a hand and try draws and discards on it"
+        );
+        println!("\t3 to evaluate a JSON hand");
+        println!("\t4 to verify a revealed wall against its hash");
+        println!("\t5 to estimate the chances of winning after each discard");
+        println!("\t6 to rate the danger of each discard against a JSON table");
+        println!("\t7 to weigh calling a discard against a JSON table");
+        println!("\t8 to play a round as the dealer against the computer");
+        println!(
+            "\t9 to play an east-only game against the computer, with three or four players"
+        );
         println!("\tany other input to quit");
 
         let mut input = String::new();
@@ -26,32 +441,19 @@ fn main() {
 
         match mode {
             1 => {
-                println!("randomly populated not yet available!");
-
-                let player = Player {
-                    seat: Wind::East,
-                    hand: vec![
-                        Tile::Wind(Wind::East),
-                        Tile::Wind(Wind::East),
-                        Tile::Wind(Wind::East),
-                        Tile::Wind(Wind::West),
-                        Tile::Wind(Wind::West),
-                        Tile::Wind(Wind::West),
-                        Tile::Wind(Wind::North),
-                        Tile::Wind(Wind::North),
-                        Tile::Wind(Wind::North),
-                        Tile::Wind(Wind::South),
-                        Tile::Wind(Wind::South),
-                        Tile::Wind(Wind::South),
-                        Tile::Dragon(Dragon::Green),
-                        Tile::Dragon(Dragon::Green),
-                    ],
-                    dealt_in: vec![],
-                    discarded: vec![],
-                    kan: vec![],
-                    riichi: false,
+                let Some(seed) = read_seed() else {
+                    continue;
                 };
 
+                let mut wall =
+                    Wall::shuffled(&Ruleset::riichi(), &mut Rng::new(seed));
+                let salt = commit_wall(&wall);
+
+                let player = Player::new(
+                    Wind::East,
+                    (0..14).filter_map(|_| wall.draw()).collect(),
+                );
+
                 print!("hand:\n\t");
                 for tile in &player.hand {
                     print!("{}, ", tile);
@@ -63,9 +465,300 @@ fn main() {
                     print!("{}, ", yaku);
                 }
                 println!();
+
+                println!("seed:\n\t{}", seed);
+                reveal_wall(&wall, &salt);
             }
             2 => {
-                println!("manually populated not yet available!");
+                let Some(hand) = read_tiles() else {
+                    continue;
+                };
+
+                // every draw and discard tried on the hand is kept, and undo
+                // goes back to try another.
+                let mut history = History::new(Player::new(Wind::East, hand));
+                let mut changed = true;
+                loop {
+                    if changed && !analyze(history.current()) {
+                        break;
+                    }
+                    let drawing = history.current().hand.len() == 13;
+                    println!(
+                        "a tile to {}, undo or redo, or nothing to stop:",
+                        if drawing { "draw" } else { "discard" }
+                    );
+                    let change = match read_line().trim() {
+                        "" => break,
+                        step @ ("undo" | "redo") => {
+                            changed = if step == "undo" {
+                                history.undo()
+                            } else {
+                                history.redo()
+                            };
+                            if !changed {
+                                println!("nothing to {}", step);
+                            }
+                            continue;
+                        }
+                        tile => match tile.parse() {
+                            Ok(tile) if drawing => Change::Draw(tile),
+                            Ok(tile) => Change::Discard(tile),
+                            Err(error) => {
+                                println!("{}", error);
+                                changed = false;
+                                continue;
+                            }
+                        },
+                    };
+                    let mut hand = history.current().hand.clone();
+                    if let Change::Draw(tile) = change {
+                        hand.push(tile);
+                    }
+                    changed =
+                        if Tile::counts(&hand).iter().any(|count| *count > 4) {
+                            println!("there are only four copies of each tile");
+                            false
+                        } else if let Err(error) = history.change(change) {
+                            println!("{}", error);
+                            false
+                        } else {
+                            true
+                        };
+                }
+            }
+            3 => {
+                println!("paste a {{\"player\": ..., \"prevalent_wind\": ...}} object on one line:");
+
+                match read_line()
+                    .parse::<Json>()
+                    .and_then(|json| evaluation_from_json(&json))
+                {
+                    Ok((player, context)) => {
+                        if let Some(furiten) =
+                            player.furiten(&context, &FuritenTracker::new())
+                        {
+                            println!("{}, the hand can't win by ron", furiten);
+                            continue;
+                        }
+                        let yakus = player.evaluate_win(&context);
+                        // the han of the yakus alone, without dora.
+                        let score = context
+                            .winning_tile
+                            .filter(|_| !yakus.is_empty())
+                            .map(|tile| {
+                                Score::new(
+                                    yakus
+                                        .iter()
+                                        .map(|yaku| {
+                                            yaku.han(player.is_menzenchin())
+                                        })
+                                        .sum(),
+                                    fu(
+                                        &player,
+                                        tile,
+                                        context.tsumo,
+                                        context.prevalent_wind,
+                                    ),
+                                )
+                            });
+                        println!(
+                            "{}",
+                            evaluation_to_json(&context, &yakus, score)
+                        );
+                    }
+                    Err(error) => println!("{}", error),
+                }
+            }
+            4 => {
+                println!("wall:");
+                let wall = read_line();
+                println!("salt:");
+                let salt = read_line();
+                println!("hash:");
+                let hash = read_line();
+
+                match wall.parse::<Wall>() {
+                    Ok(wall) if wall.hash(salt.trim()) == hash.trim() => {
+                        println!("the wall matches the hash")
+                    }
+                    Ok(_) => println!("the wall does NOT match the hash"),
+                    Err(error) => println!("{}", error),
+                }
+            }
+            5 => {
+                let Some(hand) = read_tiles() else {
+                    continue;
+                };
+                if hand.len() != 14 {
+                    println!("the hand needs the 14 tiles after a draw");
+                    continue;
+                }
+                println!("draws left (empty for 12):");
+                let draws = read_line().trim().parse().unwrap_or(12);
+                println!("samples (empty for 1000):");
+                let samples = read_line().trim().parse().unwrap_or(1000);
+                let Some(seed) = read_seed() else {
+                    continue;
+                };
+
+                let player = Player::new(Wind::East, hand);
+                let simulation = Simulation::new(samples, draws, seed);
+                println!("discard: tenpai, tsumo");
+                for outcome in player.simulate(&[], &[], &simulation) {
+                    println!(
+                        "\t{}: {:.1}%, {:.1}%",
+                        outcome.discard,
+                        outcome.tenpai * 100.0,
+                        outcome.tsumo * 100.0
+                    );
+                }
+            }
+            6 => {
+                println!("paste a {{\"player\": ..., \"opponents\": [...]}} object on one line:");
+
+                match read_line()
+                    .parse::<Json>()
+                    .and_then(|json| table_from_json(&json))
+                {
+                    Ok((_, opponents, _)) if opponents.is_empty() => {
+                        println!("there are no opponents to defend against")
+                    }
+                    Ok((player, opponents, dora_indicators)) => {
+                        println!("safest first:");
+                        for danger in
+                            player.assess_danger(&opponents, &dora_indicators)
+                        {
+                            let reasons: Vec<String> = danger
+                                .reasons
+                                .iter()
+                                .map(|reason| reason.to_string())
+                                .collect();
+                            println!(
+                                "\t{}: {:.1}% against {:?}, {}",
+                                danger.tile,
+                                danger.danger,
+                                opponents[danger.against].seat,
+                                reasons.join(", ")
+                            );
+                        }
+                    }
+                    Err(error) => println!("{}", error),
+                }
+            }
+            7 => {
+                println!("paste a {{\"player\": ..., \"opponents\": [...], \"prevalent_wind\": ...}} object on one line:");
+                let table = read_line().parse::<Json>().and_then(|json| {
+                    let prevalent_wind =
+                        Wind::from_json(json.get("prevalent_wind")?)?;
+                    Ok((table_from_json(&json)?, prevalent_wind))
+                });
+                let (table, prevalent_wind) = match table {
+                    Ok(table) => table,
+                    Err(error) => {
+                        println!("{}", error);
+                        continue;
+                    }
+                };
+                let (player, opponents, dora_indicators) = table;
+
+                println!("the discarded tile:");
+                let tile: Tile = match read_line().trim().parse() {
+                    Ok(tile) => tile,
+                    Err(error) => {
+                        println!("{}", error);
+                        continue;
+                    }
+                };
+                println!("discarded by the player to the left? (y/n):");
+                let can_chi = read_line().trim() == "y";
+
+                let advice = player.advise_calls(
+                    tile,
+                    can_chi,
+                    &opponents,
+                    &dora_indicators,
+                    prevalent_wind,
+                    Ruleset::riichi().kuikae,
+                );
+                if advice.is_empty() {
+                    println!("the hand can't call {}", tile);
+                }
+                for advice in advice {
+                    println!(
+                        "\t{}: {}{}",
+                        if advice.recommended { "call" } else { "pass" },
+                        advice.explanation,
+                        match advice.discard {
+                            Some(discard) =>
+                                format!(", then discard {}", discard),
+                            None => String::new(),
+                        }
+                    );
+                }
+            }
+            8 => {
+                let Some(seed) = read_seed() else {
+                    continue;
+                };
+                let wall =
+                    Wall::shuffled(&Ruleset::riichi(), &mut Rng::new(seed));
+                let round = Round::new(
+                    Ruleset::riichi(),
+                    wall,
+                    Wind::East,
+                    0,
+                    vec![25000; 4],
+                );
+                let salt = commit_wall(&round.wall);
+                let Some(round) = play_round(round) else {
+                    return;
+                };
+
+                print_result(&round);
+                reveal_wall(&round.wall, &salt);
+            }
+            9 => {
+                let Some(seed) = read_seed() else {
+                    continue;
+                };
+                println!("three players? (y/n):");
+                let rules = match read_line().trim() {
+                    "y" => Ruleset::sanma(),
+                    _ => Ruleset::riichi(),
+                };
+                let ruleset = Ruleset {
+                    length: GameLength::EastOnly,
+                    ..rules
+                };
+                let mut game = Game::new(ruleset, Rng::new(seed));
+                while let Some(round) = game.deal() {
+                    println!(
+                        "{:?} {}, {} honba, {} riichi sticks",
+                        game.prevalent_wind,
+                        game.hand + 1,
+                        game.honba,
+                        game.riichi_sticks
+                    );
+                    let salt = commit_wall(&round.wall);
+                    let Some(round) = play_round(round) else {
+                        return;
+                    };
+                    print_result(&round);
+                    reveal_wall(&round.wall, &salt);
+                    game.settle(&round);
+                }
+
+                for (place, placement) in game.placements().iter().enumerate() {
+                    println!(
+                        "{}. {:?} with {} ({:+})",
+                        place + 1,
+                        // the seat each player had in the first round.
+                        [Wind::East, Wind::South, Wind::West, Wind::North]
+                            [placement.position],
+                        placement.score,
+                        placement.result
+                    );
+                }
             }
             _ => {
                 break;
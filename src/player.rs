@@ -1,219 +1,464 @@
 use crate::{
-    tile::{Dragon, Simple, Tile, Wind},
+    call::{advise_calls, CallAdvice},
+    decompose::Set,
+    defense::{assess_danger, Danger},
+    efficiency::{
+        acceptance, recommend_discards, unseen, Acceptance, DiscardCandidate,
+    },
+    furiten::{Furiten, FuritenTracker},
+    lookup,
+    potential::{potential, YakuPotential},
+    riichi::{advise_riichi, RiichiAdvice, Situation},
+    ruleset::Kuikae,
+    score::reading,
+    simulation::{simulate, Outcome, Simulation},
+    tile::{Bonus, Dragon, Tile, Wind},
+    wait::{waits, Wait},
     yaku::Yaku,
 };
 
 #[derive(Clone)]
 pub struct Player {
     pub seat: Wind,
+    /// the concealed tiles.
     pub hand: Vec<Tile>,
+    /// the tiles of called sets, three per set. an open kan keeps three of
+    /// its tiles here as well as its entry in `kan`.
     pub dealt_in: Vec<Tile>,
+    /// the player's river. a discard someone called moves into the
+    /// caller's `dealt_in` instead, so every tile is shown only once.
     pub discarded: Vec<Tile>,
+    /// the discards that were called away, still part of the river for
+    /// furiten and for reading the hand. see `river`.
+    pub called: Vec<Tile>,
+    /// since declaring riichi, the tiles the others discarded that the
+    /// player didn't win on, which they can't win on any more.
+    pub passed: Vec<Tile>,
+    /// one tile per declared quad, open or concealed.
     pub kan: Vec<Tile>,
+    /// flowers and seasons set aside by `set_aside_bonus`, and the norths
+    /// set aside as nukidora in sanma.
+    pub bonus: Vec<Tile>,
     pub riichi: bool,
 }
 
-impl Player {
-    pub fn evaluate_yakus(self, prevalent_wind: Wind) -> Vec<Yaku> {
-        let mut yakus = vec![];
+/// the circumstances of a win that can't be read off the hand itself.
+#[derive(Clone, Copy)]
+pub struct WinContext {
+    pub prevalent_wind: Wind,
+    /// won on a self-drawn tile rather than on a discard.
+    pub tsumo: bool,
+    /// won on the last tile of the live wall, see `Wall::is_haitei`.
+    pub last_tile: bool,
+    /// won on the replacement tile drawn after declaring a kan.
+    pub after_kan: bool,
+    /// the tile that completed the hand, needed to tell whether a ron is
+    /// furiten.
+    pub winning_tile: Option<Tile>,
+}
 
-        let mut hand = self.hand.clone();
-        hand.sort();
+impl WinContext {
+    pub fn new(prevalent_wind: Wind) -> WinContext {
+        WinContext {
+            prevalent_wind,
+            tsumo: false,
+            last_tile: false,
+            after_kan: false,
+            winning_tile: None,
+        }
+    }
+}
 
-        let mut dealt_in = self.dealt_in.clone();
-        dealt_in.sort();
+impl Player {
+    /// a player with a closed `hand` who hasn't discarded, called or
+    /// set aside anything yet.
+    pub fn new(seat: Wind, hand: Vec<Tile>) -> Player {
+        Player {
+            seat,
+            hand,
+            dealt_in: vec![],
+            discarded: vec![],
+            called: vec![],
+            passed: vec![],
+            kan: vec![],
+            bonus: vec![],
+            riichi: false,
+        }
+    }
 
-        let mut all_tiles_filtered = [hand.clone(), dealt_in.clone()].concat();
-        all_tiles_filtered.sort();
+    pub fn evaluate_yakus(&self, prevalent_wind: Wind) -> Vec<Yaku> {
+        let mut yakus = vec![];
+        let counts = Tile::counts(&self.hand);
+        // triplets count whether they're concealed or called.
+        let sets =
+            Tile::counts(&[self.hand.clone(), self.meld_tiles()].concat());
+        let count = |tile: Tile| sets[tile.index().unwrap_or_default()];
+
+        // checks for seat wind and prevalent wind.
+        let mut winds = 0;
+        for wind_type in [Wind::East, Wind::North, Wind::South, Wind::West] {
+            if count(Tile::Wind(wind_type)) >= 3 {
+                winds += 1;
+                if wind_type == prevalent_wind {
+                    yakus.push(Yaku::PrevalentWind(wind_type));
+                } else if wind_type == self.seat {
+                    yakus.push(Yaku::SeatWind(wind_type));
+                }
+            }
+        }
+        if winds == 4 {
+            yakus.push(Yaku::FourBigWinds);
+        }
 
-        #[derive(Clone)]
-        struct HandConfiguration {
-            triplets: Vec<Vec<Tile>>,
-            pair: Vec<Tile>,
+        // checks for dragon.
+        let mut dragons = 0;
+        for dragon_type in [Dragon::Green, Dragon::Red, Dragon::White] {
+            if count(Tile::Dragon(dragon_type)) >= 3 {
+                dragons += 1;
+                yakus.push(Yaku::Dragons(dragon_type));
+            }
+        }
+        if dragons == 3 {
+            yakus.push(Yaku::BigThreeDragons);
         }
 
-        // permutation of possible configurations for the player's hand. there
-        // are 5 possible configurations in total considering they're always 4
-        // triplets and 1 pair. invalid configurations are discarded.
-        let mut hand_permutations: Vec<HandConfiguration> = vec![];
+        // checks for seven pairs. needs to be the concealed hand because a
+        // kan invalidates this yaku.
+        if self.hand.len() == 14
+            && counts.iter().all(|count| *count == 0 || *count == 2)
         {
-            for index in 0..12 {
-                let mut permutation = all_tiles_filtered.clone();
-                let pair =
-                    [permutation.remove(index + 1), permutation.remove(index)];
+            yakus.push(Yaku::SevenPairs);
+        }
 
-                if pair[0] != pair[1] {
-                    continue;
-                }
+        if self.riichi && !yakus.is_empty() {
+            yakus.push(Yaku::Riichi);
+        }
 
-                let configuration = HandConfiguration {
-                    triplets: permutation
-                        .chunks(3)
-                        .map(|x| x.to_vec())
-                        .collect(),
-                    pair: pair.to_vec(),
-                };
-
-                let is_valid_triplet = |tiles: Vec<Tile>| -> bool {
-                    if tiles[0] == tiles[1] && tiles[0] == tiles[2] {
-                        return true;
-                    }
-                    return match tiles[0] {
-                        Tile::Simple(Simple::Man(number1)) => match tiles[1] {
-                            Tile::Simple(Simple::Man(number2)) => {
-                                match tiles[2] {
-                                    Tile::Simple(Simple::Man(number3)) => {
-                                        number1 == number2 - 1
-                                            && number2 == number3 - 1
-                                    }
-                                    _ => false,
-                                }
-                            }
-                            _ => false,
-                        },
-                        Tile::Simple(Simple::Pin(number1)) => match tiles[1] {
-                            Tile::Simple(Simple::Pin(number2)) => {
-                                match tiles[2] {
-                                    Tile::Simple(Simple::Pin(number3)) => {
-                                        number1 == number2 - 1
-                                            && number2 == number3 - 1
-                                    }
-                                    _ => false,
-                                }
-                            }
-                            _ => false,
-                        },
-                        Tile::Simple(Simple::Sou(number1)) => match tiles[1] {
-                            Tile::Simple(Simple::Sou(number2)) => {
-                                match tiles[2] {
-                                    Tile::Simple(Simple::Sou(number3)) => {
-                                        number1 == number2 - 1
-                                            && number2 == number3 - 1
-                                    }
-                                    _ => false,
-                                }
-                            }
-                            _ => false,
-                        },
-                        _ => false,
-                    };
-                };
-
-                let mut is_valid_conf = true;
-                for triplet in configuration.triplets.clone() {
-                    if !is_valid_triplet(triplet) {
-                        is_valid_conf = false;
-                        break;
-                    }
-                }
+        yakus
+    }
 
-                if !is_valid_conf {
-                    continue;
+    /// the yakus of the flowers and seasons set aside. they only count on
+    /// top of a complete hand that has another yaku, which `evaluate_win`
+    /// checks.
+    pub fn bonus_yakus(&self) -> Vec<Yaku> {
+        let mut yakus = vec![];
+        for tile in &self.bonus {
+            if let Tile::Bonus(bonus) = tile {
+                if bonus.matches_seat(self.seat) {
+                    yakus.push(match bonus {
+                        Bonus::Flower(_) => Yaku::SeatFlower,
+                        Bonus::Season(_) => Yaku::SeatSeason,
+                    });
                 }
-
-                hand_permutations.push(configuration);
             }
         }
 
-        {
-            // checks for seat wind and prevalent wind.
-            let wind_tiles: Vec<Tile> = hand
-                .iter()
-                .cloned()
-                .filter(|x| match x {
-                    Tile::Wind(_) => true,
-                    _ => false,
-                })
-                .collect();
-
-            if wind_tiles.len() >= 3 {
-                for wind_type in
-                    [Wind::East, Wind::North, Wind::South, Wind::West]
-                {
-                    let count = wind_tiles
-                        .iter()
-                        .filter(|t| **t == Tile::Wind(wind_type))
-                        .count();
-
-                    if count >= 3 {
-                        if wind_type == prevalent_wind {
-                            yakus.push(Yaku::PrevalentWind(wind_type));
-                        } else if wind_type == self.seat {
-                            yakus.push(Yaku::SeatWind(wind_type));
-                        }
-                    }
-                }
-            }
+        let flowers = self
+            .bonus
+            .iter()
+            .filter(|x| matches!(x, Tile::Bonus(Bonus::Flower(_))))
+            .count();
+        let seasons = self
+            .bonus
+            .iter()
+            .filter(|x| matches!(x, Tile::Bonus(Bonus::Season(_))))
+            .count();
+        if flowers == 4 {
+            yakus.push(Yaku::AllFlowers);
+        }
+        if seasons == 4 {
+            yakus.push(Yaku::AllSeasons);
         }
+        yakus
+    }
 
-        {
-            // checks for dragon.
-            let dragon_tiles: Vec<Tile> = hand
-                .iter()
-                .cloned()
-                .filter(|x| match x {
-                    Tile::Dragon(_) => true,
-                    _ => false,
-                })
-                .collect();
-
-            if dragon_tiles.len() >= 3 {
-                for dragon_type in [Dragon::Green, Dragon::Red, Dragon::White] {
-                    let count = dragon_tiles
-                        .iter()
-                        .filter(|t| **t == Tile::Dragon(dragon_type))
-                        .count();
-
-                    if count >= 3 {
-                        yakus.push(Yaku::Dragons(dragon_type));
-                    }
-                }
-            }
+    /// moves every flower and season out of the hand into `bonus` and
+    /// returns how many replacement tiles the player has to draw.
+    pub fn set_aside_bonus(&mut self) -> usize {
+        let before = self.bonus.len();
+        let (bonus, hand) = self
+            .hand
+            .iter()
+            .partition(|tile| matches!(tile, Tile::Bonus(_)));
+        self.hand = hand;
+        self.bonus.extend::<Vec<Tile>>(bonus);
+        self.bonus.len() - before
+    }
+
+    /// every tile the player discarded, the called ones included after
+    /// the rest.
+    pub fn river(&self) -> Vec<Tile> {
+        [self.discarded.clone(), self.called.clone()].concat()
+    }
+
+    /// the norths set aside, each worth a dora.
+    pub fn nukidora(&self) -> usize {
+        self.bonus
+            .iter()
+            .filter(|tile| **tile == Tile::Wind(Wind::North))
+            .count()
+    }
+
+    /// `evaluate_yakus` plus the yakus that depend on how the winning tile
+    /// was obtained or where it sits, and the bonus tiles when the hand is
+    /// complete.
+    pub fn evaluate_win(&self, context: &WinContext) -> Vec<Yaku> {
+        let mut yakus = self.evaluate_yakus(context.prevalent_wind);
+
+        if context.last_tile {
+            yakus.push(if context.tsumo {
+                Yaku::UnderTheSea
+            } else {
+                Yaku::UnderTheRiver
+            });
         }
 
-        {
-            // checks for seven pairs
-            let mut unique_check = Tile::None;
-            // needs to be the original hand because we removed Kans (and Kan
-            // invalidates this yaku).
-            for pair in self.hand.chunks(2).into_iter() {
-                if pair[0] == pair[1] && pair[0] != unique_check {
-                    unique_check = pair[0];
+        if context.after_kan && context.tsumo {
+            yakus.push(Yaku::AfterAKan);
+        }
+
+        if context.tsumo && self.is_menzenchin() {
+            yakus.push(Yaku::FullyConcealedHand);
+        }
+
+        // pinfu is read off the same reading of the hand that the fu are.
+        let pinfu = context.winning_tile.and_then(|tile| {
+            reading(self, tile, context.tsumo, context.prevalent_wind)
+        });
+        if pinfu.is_some_and(|reading| reading.pinfu) {
+            yakus.push(Yaku::Pinfu);
+        }
+
+        if !yakus.is_empty() && self.is_complete() {
+            yakus.extend(self.bonus_yakus());
+        }
+
+        yakus
+    }
+
+    /// the furiten that forbids winning on a discard with this complete
+    /// hand, none for a tsumo or when the winning tile isn't known.
+    pub fn furiten(
+        &self,
+        context: &WinContext,
+        tracker: &FuritenTracker,
+    ) -> Option<Furiten> {
+        let tile = context.winning_tile.filter(|_| !context.tsumo)?;
+        let mut waiting = self.clone();
+        let position = waiting.hand.iter().position(|t| *t == tile)?;
+        waiting.hand.remove(position);
+        tracker.furiten(&waiting)
+    }
+
+    /// the number of sets outside of the concealed hand: the called sets
+    /// plus the concealed kans.
+    pub fn melds(&self) -> usize {
+        let concealed_kans = self
+            .kan
+            .iter()
+            .filter(|tile| !self.dealt_in.contains(tile))
+            .count();
+        self.dealt_in.len() / 3 + concealed_kans
+    }
+
+    /// the called sets and the concealed kans, a quad as its triplet.
+    pub fn meld_sets(&self) -> Vec<Set> {
+        let mut sets: Vec<Set> = self
+            .dealt_in
+            .chunks(3)
+            .map(|chunk| {
+                let mut chunk = chunk.to_vec();
+                chunk.sort_by_key(|tile| tile.index());
+                if chunk[0] == chunk[1] {
+                    Set::Triplet(chunk[0])
                 } else {
-                    unique_check = Tile::None;
-                    break;
+                    Set::Sequence(chunk[0])
                 }
-            }
-            if unique_check != Tile::None {
-                yakus.push(Yaku::SevenPairs);
-            }
+            })
+            .collect();
+        sets.extend(
+            self.kan
+                .iter()
+                .filter(|tile| !self.dealt_in.contains(tile))
+                .map(|tile| Set::Triplet(*tile)),
+        );
+        sets
+    }
+
+    /// every tile of the called sets and quads, four per quad.
+    pub fn meld_tiles(&self) -> Vec<Tile> {
+        let mut tiles = self.dealt_in.clone();
+        for tile in &self.kan {
+            let copies = if self.dealt_in.contains(tile) { 1 } else { 4 };
+            tiles.extend([*tile].repeat(copies));
         }
+        tiles
+    }
 
-        if self.riichi && !yakus.is_empty() {
-            yakus.push(Yaku::Riichi);
+    /// the tiles this player can see: their own hand, every river and meld
+    /// at the table and the dora indicators.
+    pub fn visible_tiles(
+        &self,
+        opponents: &[Player],
+        dora_indicators: &[Tile],
+    ) -> Vec<Tile> {
+        let mut visible = self.hand.clone();
+        for player in std::iter::once(self).chain(opponents) {
+            visible.extend(player.meld_tiles());
+            visible.extend(&player.discarded);
+            visible.extend(&player.bonus);
         }
+        visible.extend(dora_indicators);
+        visible
+    }
 
-        yakus
+    /// the acceptance of every discard from the player's hand after a draw,
+    /// counting only the copies the player can't see.
+    pub fn acceptance(
+        &self,
+        opponents: &[Player],
+        dora_indicators: &[Tile],
+    ) -> Vec<Acceptance> {
+        let unseen = unseen(&self.visible_tiles(opponents, dora_indicators));
+        acceptance(&self.hand, self.melds(), &unseen)
+    }
+
+    /// every discard from the player's hand after a draw, best first, see
+    /// `efficiency::recommend_discards`.
+    pub fn recommend_discards(
+        &self,
+        opponents: &[Player],
+        dora_indicators: &[Tile],
+        prevalent_wind: Wind,
+    ) -> Vec<DiscardCandidate> {
+        let unseen = unseen(&self.visible_tiles(opponents, dora_indicators));
+        let doras: Vec<Tile> = dora_indicators
+            .iter()
+            .map(|indicator| indicator.indicate_dora())
+            .collect();
+        recommend_discards(self, &unseen, &doras, prevalent_wind)
+    }
+
+    /// the danger of discarding each tile of the hand against the
+    /// opponents, safest first, see `defense::assess_danger`.
+    pub fn assess_danger(
+        &self,
+        opponents: &[Player],
+        dora_indicators: &[Tile],
+    ) -> Vec<Danger> {
+        assess_danger(
+            self,
+            opponents,
+            &self.visible_tiles(opponents, dora_indicators),
+        )
+    }
+
+    /// whether to call an opponent's discarded `tile`, chi only when it
+    /// comes from the player to the left, see `call::advise_calls`.
+    pub fn advise_calls(
+        &self,
+        tile: Tile,
+        can_chi: bool,
+        opponents: &[Player],
+        dora_indicators: &[Tile],
+        prevalent_wind: Wind,
+        kuikae: Kuikae,
+    ) -> Vec<CallAdvice> {
+        let unseen = unseen(&self.visible_tiles(opponents, dora_indicators));
+        let doras: Vec<Tile> = dora_indicators
+            .iter()
+            .map(|indicator| indicator.indicate_dora())
+            .collect();
+        advise_calls(
+            self,
+            tile,
+            can_chi,
+            &unseen,
+            &doras,
+            prevalent_wind,
+            kuikae,
+        )
+    }
+
+    /// riichi or dama for a closed tenpai hand, see `riichi::advise_riichi`.
+    pub fn advise_riichi(
+        &self,
+        opponents: &[Player],
+        dora_indicators: &[Tile],
+        prevalent_wind: Wind,
+        situation: &Situation,
+    ) -> Option<RiichiAdvice> {
+        let unseen = unseen(&self.visible_tiles(opponents, dora_indicators));
+        advise_riichi(self, &unseen, dora_indicators, prevalent_wind, situation)
+    }
+
+    /// the chances of reaching tenpai and winning by tsumo after each
+    /// discard, drawing from the tiles the player can't see, see
+    /// `simulation::simulate`.
+    pub fn simulate(
+        &self,
+        opponents: &[Player],
+        dora_indicators: &[Tile],
+        simulation: &Simulation,
+    ) -> Vec<Outcome> {
+        let unseen = unseen(&self.visible_tiles(opponents, dora_indicators));
+        simulate(&self.hand, self.melds(), &unseen, simulation)
+    }
+
+    /// the yakus the hand can still reach, see `potential::potential`.
+    pub fn potential(&self, prevalent_wind: Wind) -> Vec<YakuPotential> {
+        potential(self, prevalent_wind)
+    }
+
+    /// see `shanten::shanten`, read from the tables in `lookup`.
+    pub fn shanten(&self) -> i8 {
+        lookup::shanten(&Tile::counts(&self.hand), self.melds())
+    }
+
+    /// whether the concealed tiles and the melds make a complete hand, see
+    /// `lookup::is_complete`.
+    pub fn is_complete(&self) -> bool {
+        lookup::is_complete(&Tile::counts(&self.hand), self.melds())
+    }
+
+    /// every tile that completes the hand, see `wait::waits`. tiles whose
+    /// four copies are all in the player's hand or melds are flagged as
+    /// impossible.
+    pub fn waits(&self) -> Vec<Wait> {
+        let mut held =
+            Tile::counts(&[self.hand.clone(), self.dealt_in.clone()].concat());
+        for index in self.kan.iter().filter_map(|tile| tile.index()) {
+            held[index] = 4;
+        }
+
+        let mut waits = waits(&self.hand, self.melds());
+        for wait in waits.iter_mut() {
+            if let Some(index) = wait.tile.index() {
+                wait.impossible = held[index] >= 4;
+            }
+        }
+        waits
     }
 
     pub fn is_menzenchin(&self) -> bool {
-        return self.dealt_in.is_empty();
+        self.dealt_in.is_empty()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        player::Player,
-        tile::{Dragon, Simple, Tile, Wind},
+        player::{Player, WinContext},
+        shanten::shanten,
+        tile::{tiles, Bonus, Dragon, Simple, Tile, Wind},
         yaku::Yaku,
     };
 
     #[test]
     fn is_menzenchin() {
-        let mut player = Player {
-            seat: Wind::East,
-            hand: vec![
+        let mut player = Player::new(
+            Wind::East,
+            vec![
                 Tile::Dragon(Dragon::Red),
                 Tile::Dragon(Dragon::Red),
                 Tile::Dragon(Dragon::Red),
@@ -229,11 +474,7 @@ mod tests {
                 Tile::Dragon(Dragon::Green),
                 Tile::Dragon(Dragon::Green),
             ],
-            dealt_in: vec![],
-            discarded: vec![],
-            kan: vec![],
-            riichi: false,
-        };
+        );
 
         assert!(player.is_menzenchin());
 
@@ -243,13 +484,19 @@ mod tests {
         }
 
         assert!(!player.is_menzenchin());
+        assert_eq!(player.melds(), 1);
+
+        player.kan.push(Tile::Simple(Simple::Man(1)));
+        assert_eq!(player.melds(), 1);
+        player.kan.push(Tile::Dragon(Dragon::Red));
+        assert_eq!(player.melds(), 2);
     }
 
     #[test]
     fn dragon() {
-        let mut player = Player {
-            seat: Wind::East,
-            hand: vec![
+        let mut player = Player::new(
+            Wind::East,
+            vec![
                 Tile::Wind(Wind::East),
                 Tile::Wind(Wind::East),
                 Tile::Wind(Wind::East),
@@ -265,14 +512,9 @@ mod tests {
                 Tile::Dragon(Dragon::Green),
                 Tile::Dragon(Dragon::Green),
             ],
-            dealt_in: vec![],
-            discarded: vec![],
-            kan: vec![],
-            riichi: false,
-        };
+        );
 
         assert!(player
-            .clone()
             .evaluate_yakus(Wind::East)
             .contains(&Yaku::Dragons(Dragon::Green)));
 
@@ -280,16 +522,53 @@ mod tests {
         player.hand.push(Tile::Simple(Simple::Man(1)));
 
         assert!(!player
-            .clone()
             .evaluate_yakus(Wind::East)
             .contains(&Yaku::Dragons(Dragon::Green)));
     }
 
     #[test]
-    fn seat_wind() {
+    fn big_three_dragons() {
         let mut player = Player {
-            seat: Wind::West,
-            hand: vec![
+            dealt_in: vec![
+                Tile::Simple(Simple::Man(1)),
+                Tile::Simple(Simple::Man(2)),
+                Tile::Simple(Simple::Man(3)),
+                Tile::Dragon(Dragon::White),
+                Tile::Dragon(Dragon::White),
+                Tile::Dragon(Dragon::White),
+            ],
+            ..Player::new(
+                Wind::South,
+                vec![
+                    Tile::Dragon(Dragon::Green),
+                    Tile::Dragon(Dragon::Green),
+                    Tile::Dragon(Dragon::Green),
+                    Tile::Dragon(Dragon::Red),
+                    Tile::Dragon(Dragon::Red),
+                    Tile::Dragon(Dragon::Red),
+                    Tile::Simple(Simple::Pin(3)),
+                    Tile::Simple(Simple::Pin(3)),
+                ],
+            )
+        };
+
+        // the called white dragons count as much as the concealed ones.
+        let yakus = player.evaluate_yakus(Wind::East);
+        assert!(yakus.contains(&Yaku::Dragons(Dragon::White)));
+        assert!(yakus.contains(&Yaku::BigThreeDragons));
+
+        player.dealt_in.truncate(3);
+        player.hand.extend([Tile::Simple(Simple::Sou(4)); 3]);
+        assert!(!player
+            .evaluate_yakus(Wind::East)
+            .contains(&Yaku::BigThreeDragons));
+    }
+
+    #[test]
+    fn seat_wind() {
+        let mut player = Player::new(
+            Wind::West,
+            vec![
                 Tile::Simple(Simple::Man(1)),
                 Tile::Simple(Simple::Man(2)),
                 Tile::Simple(Simple::Man(3)),
@@ -305,14 +584,9 @@ mod tests {
                 Tile::Wind(Wind::West),
                 Tile::Wind(Wind::West),
             ],
-            dealt_in: vec![],
-            discarded: vec![],
-            kan: vec![],
-            riichi: false,
-        };
+        );
 
         assert!(player
-            .clone()
             .evaluate_yakus(Wind::East)
             .contains(&Yaku::SeatWind(Wind::West)));
 
@@ -320,16 +594,15 @@ mod tests {
         player.hand.push(Tile::Simple(Simple::Man(1)));
 
         assert!(!player
-            .clone()
             .evaluate_yakus(Wind::East)
             .contains(&Yaku::SeatWind(Wind::West)));
     }
 
     #[test]
     fn prevalent_wind() {
-        let mut player = Player {
-            seat: Wind::West,
-            hand: vec![
+        let mut player = Player::new(
+            Wind::West,
+            vec![
                 Tile::Simple(Simple::Man(1)),
                 Tile::Simple(Simple::Man(2)),
                 Tile::Simple(Simple::Man(3)),
@@ -345,14 +618,9 @@ mod tests {
                 Tile::Wind(Wind::East),
                 Tile::Wind(Wind::East),
             ],
-            dealt_in: vec![],
-            discarded: vec![],
-            kan: vec![],
-            riichi: false,
-        };
+        );
 
         assert!(player
-            .clone()
             .evaluate_yakus(Wind::East)
             .contains(&Yaku::PrevalentWind(Wind::East)));
 
@@ -360,7 +628,6 @@ mod tests {
         player.hand.push(Tile::Simple(Simple::Man(1)));
 
         assert!(!player
-            .clone()
             .evaluate_yakus(Wind::East)
             .contains(&Yaku::PrevalentWind(Wind::East)));
     }
@@ -368,74 +635,261 @@ mod tests {
     #[test]
     fn riichi() {
         let mut player = Player {
-            seat: Wind::East,
-            hand: vec![
+            riichi: true,
+            ..Player::new(
+                Wind::East,
+                vec![
+                    Tile::Wind(Wind::East),
+                    Tile::Wind(Wind::East),
+                    Tile::Wind(Wind::East),
+                    Tile::Dragon(Dragon::Red),
+                    Tile::Dragon(Dragon::Green),
+                    Tile::Dragon(Dragon::White),
+                    Tile::Simple(Simple::Man(1)),
+                    Tile::Simple(Simple::Man(2)),
+                    Tile::Simple(Simple::Man(3)),
+                    Tile::Simple(Simple::Pin(1)),
+                    Tile::Simple(Simple::Pin(2)),
+                    Tile::Simple(Simple::Pin(3)),
+                    Tile::Simple(Simple::Pin(3)),
+                    Tile::Simple(Simple::Pin(3)),
+                ],
+            )
+        };
+
+        assert!(player.evaluate_yakus(Wind::East).contains(&Yaku::Riichi));
+        player.riichi = false;
+        assert!(!player.evaluate_yakus(Wind::East).contains(&Yaku::Riichi));
+    }
+
+    #[test]
+    fn seven_pairs() {
+        let mut player = Player {
+            riichi: true,
+            ..Player::new(
+                Wind::East,
+                vec![
+                    Tile::Wind(Wind::East),
+                    Tile::Wind(Wind::East),
+                    Tile::Dragon(Dragon::Red),
+                    Tile::Dragon(Dragon::Red),
+                    Tile::Dragon(Dragon::Green),
+                    Tile::Dragon(Dragon::Green),
+                    Tile::Simple(Simple::Man(1)),
+                    Tile::Simple(Simple::Man(1)),
+                    Tile::Simple(Simple::Pin(1)),
+                    Tile::Simple(Simple::Pin(1)),
+                    Tile::Simple(Simple::Pin(2)),
+                    Tile::Simple(Simple::Pin(2)),
+                    Tile::Simple(Simple::Pin(3)),
+                    Tile::Simple(Simple::Pin(3)),
+                ],
+            )
+        };
+
+        assert!(player
+            .evaluate_yakus(Wind::East)
+            .contains(&Yaku::SevenPairs));
+
+        player.hand.pop();
+        player.hand.push(Tile::Simple(Simple::Man(1)));
+
+        assert!(!player
+            .evaluate_yakus(Wind::East)
+            .contains(&Yaku::SevenPairs));
+    }
+
+    #[test]
+    fn bonus_tiles() {
+        let mut player = Player::new(
+            Wind::South,
+            vec![
                 Tile::Wind(Wind::East),
                 Tile::Wind(Wind::East),
                 Tile::Wind(Wind::East),
-                Tile::Dragon(Dragon::Red),
-                Tile::Dragon(Dragon::Green),
-                Tile::Dragon(Dragon::White),
+                Tile::Bonus(Bonus::Flower(2)),
                 Tile::Simple(Simple::Man(1)),
                 Tile::Simple(Simple::Man(2)),
                 Tile::Simple(Simple::Man(3)),
                 Tile::Simple(Simple::Pin(1)),
+                Tile::Bonus(Bonus::Season(3)),
                 Tile::Simple(Simple::Pin(2)),
                 Tile::Simple(Simple::Pin(3)),
                 Tile::Simple(Simple::Pin(3)),
                 Tile::Simple(Simple::Pin(3)),
+                Tile::Dragon(Dragon::Green),
+                Tile::Dragon(Dragon::Green),
+                Tile::Dragon(Dragon::Green),
             ],
-            dealt_in: vec![],
-            discarded: vec![],
-            kan: vec![],
-            riichi: true,
-        };
+        );
 
-        assert!(player
-            .clone()
+        assert_eq!(player.set_aside_bonus(), 2);
+        assert_eq!(player.hand.len(), 14);
+        assert_eq!(player.set_aside_bonus(), 0);
+
+        let context = WinContext::new(Wind::East);
+        let yakus = player.evaluate_win(&context);
+        assert!(yakus.contains(&Yaku::SeatFlower));
+        assert!(!yakus.contains(&Yaku::SeatSeason));
+        assert!(!yakus.contains(&Yaku::AllFlowers));
+        assert!(!player
             .evaluate_yakus(Wind::East)
-            .contains(&Yaku::Riichi));
-        player.riichi = false;
-        assert!(!player.evaluate_yakus(Wind::East).contains(&Yaku::Riichi));
+            .contains(&Yaku::SeatFlower));
+
+        // a flower alone doesn't make a hand without a yaku win.
+        player.hand[0] = Tile::Simple(Simple::Sou(7));
+        player.hand[1] = Tile::Simple(Simple::Sou(8));
+        player.hand[2] = Tile::Simple(Simple::Sou(9));
+        player.hand[11] = Tile::Simple(Simple::Man(7));
+        player.hand[12] = Tile::Simple(Simple::Man(8));
+        player.hand[13] = Tile::Simple(Simple::Man(9));
+        assert_eq!(player.shanten(), -1);
+        assert_eq!(player.evaluate_win(&context), vec![]);
+
+        // nor does it count on a hand that isn't complete.
+        player.hand[..3].fill(Tile::Wind(Wind::East));
+        player.hand[13] = Tile::Dragon(Dragon::Red);
+        let yakus = player.evaluate_win(&context);
+        assert!(yakus.contains(&Yaku::PrevalentWind(Wind::East)));
+        assert!(!yakus.contains(&Yaku::SeatFlower));
     }
 
     #[test]
-    fn seven_pairs() {
-        let mut player = Player {
-            seat: Wind::East,
-            hand: vec![
-                Tile::Wind(Wind::East),
-                Tile::Wind(Wind::East),
-                Tile::Dragon(Dragon::Red),
-                Tile::Dragon(Dragon::Red),
-                Tile::Dragon(Dragon::Green),
-                Tile::Dragon(Dragon::Green),
-                Tile::Simple(Simple::Man(1)),
+    fn is_complete() {
+        let mut player = Player::new(
+            Wind::East,
+            tiles("M1 M2 M3 P1 P2 P3 P3 P3 S4 S5 S6 GD GD GD"),
+        );
+        assert!(player.is_complete());
+        assert_eq!(player.shanten(), -1);
+
+        // a concealed kan counts as one of the four sets.
+        player.hand.truncate(11);
+        player.kan.push(Tile::Dragon(Dragon::Green));
+        assert!(player.is_complete());
+        player.hand[10] = Tile::Dragon(Dragon::Red);
+        assert!(!player.is_complete());
+        assert_eq!(player.shanten(), shanten(&player.hand, player.melds()));
+
+        // more than four copies fall back to the search.
+        let player = Player::new(
+            Wind::East,
+            tiles("M1 M1 M1 M1 M1 M2 M3 P3 P3 P3 S4 S5 S6 RD"),
+        );
+        assert_eq!(player.shanten(), shanten(&player.hand, 0));
+    }
+
+    #[test]
+    fn win_context() {
+        let player = Player::new(
+            Wind::East,
+            vec![
                 Tile::Simple(Simple::Man(1)),
-                Tile::Simple(Simple::Pin(1)),
+                Tile::Simple(Simple::Man(2)),
+                Tile::Simple(Simple::Man(3)),
                 Tile::Simple(Simple::Pin(1)),
                 Tile::Simple(Simple::Pin(2)),
-                Tile::Simple(Simple::Pin(2)),
                 Tile::Simple(Simple::Pin(3)),
                 Tile::Simple(Simple::Pin(3)),
+                Tile::Simple(Simple::Pin(3)),
+                Tile::Simple(Simple::Sou(4)),
+                Tile::Simple(Simple::Sou(5)),
+                Tile::Simple(Simple::Sou(6)),
+                Tile::Dragon(Dragon::Green),
+                Tile::Dragon(Dragon::Green),
+                Tile::Dragon(Dragon::Green),
             ],
-            dealt_in: vec![],
-            discarded: vec![],
-            kan: vec![],
-            riichi: true,
-        };
+        );
+
+        let mut context = WinContext::new(Wind::East);
+        context.last_tile = true;
+        let yakus = player.evaluate_win(&context);
+        assert!(yakus.contains(&Yaku::UnderTheRiver));
+        assert!(!yakus.contains(&Yaku::UnderTheSea));
+
+        context.tsumo = true;
+        context.after_kan = true;
+        let yakus = player.evaluate_win(&context);
+        assert!(yakus.contains(&Yaku::UnderTheSea));
+        assert!(yakus.contains(&Yaku::AfterAKan));
+    }
 
-        assert!(player
-            .clone()
-            .evaluate_yakus(Wind::East)
-            .contains(&Yaku::SevenPairs));
+    #[test]
+    fn waits() {
+        let mut player = Player {
+            dealt_in: vec![Tile::Simple(Simple::Pin(1)); 3],
+            kan: vec![
+                Tile::Simple(Simple::Pin(1)),
+                Tile::Simple(Simple::Pin(4)),
+            ],
+            ..Player::new(
+                Wind::East,
+                vec![
+                    Tile::Simple(Simple::Man(1)),
+                    Tile::Simple(Simple::Man(2)),
+                    Tile::Simple(Simple::Man(3)),
+                    Tile::Simple(Simple::Pin(2)),
+                    Tile::Simple(Simple::Pin(3)),
+                    Tile::Simple(Simple::Sou(4)),
+                    Tile::Simple(Simple::Sou(4)),
+                ],
+            )
+        };
 
-        player.hand.pop();
-        player.hand.push(Tile::Simple(Simple::Man(1)));
+        // both ends of the 23 pin wait are used up by the open kan of 1 pin
+        // and the concealed one of 4 pin.
+        let waits = player.waits();
+        assert_eq!(waits.len(), 2);
+        assert!(waits.iter().all(|wait| wait.impossible));
+
+        player.kan.clear();
+        player.dealt_in.push(Tile::Dragon(Dragon::Red));
+        player.dealt_in.push(Tile::Dragon(Dragon::Red));
+        player.dealt_in.push(Tile::Dragon(Dragon::Red));
+        let waits = player.waits();
+        assert!(!waits[0].impossible);
+        assert!(waits[1].tile == Tile::Simple(Simple::Pin(4)));
+        assert!(!waits[1].impossible);
+    }
 
-        assert!(!player
-            .clone()
-            .evaluate_yakus(Wind::East)
-            .contains(&Yaku::SevenPairs));
+    #[test]
+    fn acceptance() {
+        let player = Player {
+            dealt_in: vec![Tile::Simple(Simple::Pin(1)); 3],
+            discarded: vec![Tile::Simple(Simple::Pin(4))],
+            kan: vec![Tile::Wind(Wind::North)],
+            ..Player::new(
+                Wind::East,
+                vec![
+                    Tile::Simple(Simple::Man(1)),
+                    Tile::Simple(Simple::Man(2)),
+                    Tile::Simple(Simple::Man(3)),
+                    Tile::Simple(Simple::Pin(2)),
+                    Tile::Simple(Simple::Pin(3)),
+                    Tile::Simple(Simple::Sou(4)),
+                    Tile::Simple(Simple::Sou(4)),
+                    Tile::Dragon(Dragon::Red),
+                ],
+            )
+        };
+        let mut opponent = player.clone();
+        opponent.hand.clear();
+        opponent.dealt_in.clear();
+        opponent.kan.clear();
+        opponent.discarded = vec![Tile::Simple(Simple::Pin(4))];
+
+        let acceptances =
+            player.acceptance(&[opponent], &[Tile::Simple(Simple::Pin(1))]);
+        let best = &acceptances[0];
+        assert!(best.discard == Tile::Dragon(Dragon::Red));
+        assert_eq!(best.shanten, 0);
+        // every 1 pin is visible and two of the 4 pin are in rivers.
+        assert!(
+            best.tiles
+                == vec![
+                    (Tile::Simple(Simple::Pin(1)), 0),
+                    (Tile::Simple(Simple::Pin(4)), 2),
+                ]
+        );
     }
 }
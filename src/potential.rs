@@ -0,0 +1,412 @@
+use std::fmt::Display;
+
+use crate::{
+    decompose::Set,
+    player::Player,
+    shanten::{
+        combine, seven_pairs_shanten, shanten_of_blocks, standard_shanten,
+        suit_blocks, thirteen_orphans_shanten,
+    },
+    tile::{Dragon, Simple, Tile, Wind, TILE_KINDS},
+    yaku::Yaku,
+};
+
+/// how far a hand is from winning with a given yaku.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Reach {
+    /// the number of tiles that have to be exchanged to complete the hand
+    /// with the yaku, 0 when it already is.
+    Tiles(u8),
+    /// the called sets rule the yaku out, and why.
+    Impossible(&'static str),
+}
+
+impl Display for Reach {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reach::Tiles(0) => write!(f, "complete"),
+            Reach::Tiles(1) => write!(f, "in 1 tile"),
+            Reach::Tiles(tiles) => write!(f, "in {} tiles", tiles),
+            Reach::Impossible(reason) => write!(f, "impossible {}", reason),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct YakuPotential {
+    pub yaku: Yaku,
+    pub reach: Reach,
+    /// what the yaku is worth in the hand as it is, open or closed.
+    pub han: u8,
+}
+
+/// the tiles a yaku allows, and whether its sets may be sequences.
+struct Restriction {
+    allowed: fn(usize) -> bool,
+    sequences: bool,
+    reason: &'static str,
+}
+
+const ANY: Restriction = Restriction {
+    allowed: |_| true,
+    sequences: true,
+    reason: "",
+};
+
+/// the yakus the player's hand, 13 or 14 tiles, can still reach, fastest
+/// first, followed by the ones its calls ruled out. only yakus that shape
+/// the hand are covered, not those depending on luck or timing.
+pub fn potential(player: &Player, prevalent_wind: Wind) -> Vec<YakuPotential> {
+    let counts = Tile::counts(&player.hand);
+    let melds = player.meld_sets();
+    let menzenchin = player.is_menzenchin();
+    let mut potentials = vec![];
+
+    let mut push = |yaku: Yaku, reach: Reach| {
+        potentials.push(YakuPotential {
+            yaku,
+            reach,
+            han: yaku.han(menzenchin),
+        })
+    };
+    let closed = |shanten: i8| {
+        if menzenchin {
+            tiles(shanten)
+        } else {
+            Reach::Impossible("with an open hand")
+        }
+    };
+
+    let best = standard_shanten(&counts, melds.len()).min(if menzenchin {
+        seven_pairs_shanten(&counts).min(thirteen_orphans_shanten(&counts))
+    } else {
+        i8::MAX
+    });
+    push(Yaku::Riichi, closed(best));
+    push(Yaku::SevenPairs, closed(seven_pairs_shanten(&counts)));
+    push(
+        Yaku::ThirteenOrphans,
+        closed(thirteen_orphans_shanten(&counts)),
+    );
+
+    let simples = Restriction {
+        allowed: |index| !Tile::from_index(index).is_terminal_or_honor(),
+        sequences: true,
+        reason: "after calling terminals or honors",
+    };
+    push(Yaku::AllSimples, restricted(&counts, &melds, &simples, &[]));
+
+    let triplets = Restriction {
+        allowed: |_| true,
+        sequences: false,
+        reason: "after calling a sequence",
+    };
+    let all_triplets = restricted(&counts, &melds, &triplets, &[]);
+    push(Yaku::AllTriplets, all_triplets.clone());
+    push(
+        Yaku::FourConcealedTriplets,
+        match all_triplets {
+            Reach::Tiles(_) if !menzenchin => {
+                Reach::Impossible("with an open hand")
+            }
+            reach => reach,
+        },
+    );
+
+    let terminals = Restriction {
+        allowed: |index| Tile::from_index(index).is_terminal_or_honor(),
+        sequences: false,
+        reason: "after calling simples or a sequence",
+    };
+    push(
+        Yaku::AllTerminalsAndHonors,
+        restricted(&counts, &melds, &terminals, &[]),
+    );
+
+    let honors = Restriction {
+        allowed: |index| index >= 27,
+        sequences: false,
+        reason: "after calling a suit",
+    };
+    push(Yaku::AllHonors, restricted(&counts, &melds, &honors, &[]));
+
+    let half_flushes: [Restriction; 3] = [
+        Restriction {
+            allowed: |index| !(9..27).contains(&index),
+            ..ANY
+        },
+        Restriction {
+            allowed: |index| index >= 27 || (9..18).contains(&index),
+            ..ANY
+        },
+        Restriction {
+            allowed: |index| index >= 18,
+            ..ANY
+        },
+    ];
+    push(
+        Yaku::HalfFlush,
+        fastest(
+            half_flushes.iter().map(|restriction| {
+                restricted(&counts, &melds, restriction, &[])
+            }),
+        )
+        .unwrap_or(Reach::Impossible("after calling two suits")),
+    );
+
+    let full_flushes: [Restriction; 3] = [
+        Restriction {
+            allowed: |index| index < 9,
+            ..ANY
+        },
+        Restriction {
+            allowed: |index| (9..18).contains(&index),
+            ..ANY
+        },
+        Restriction {
+            allowed: |index| (18..27).contains(&index),
+            ..ANY
+        },
+    ];
+    push(
+        Yaku::FullFlush,
+        fastest(
+            full_flushes.iter().map(|restriction| {
+                restricted(&counts, &melds, restriction, &[])
+            }),
+        )
+        .unwrap_or(Reach::Impossible("after calling two suits or honors")),
+    );
+
+    let value_winds = [
+        Yaku::SeatWind(player.seat),
+        Yaku::PrevalentWind(prevalent_wind),
+    ];
+    for yaku in [Dragon::Green, Dragon::Red, Dragon::White]
+        .map(Yaku::Dragons)
+        .into_iter()
+        .chain(value_winds)
+    {
+        let tile = match yaku {
+            Yaku::Dragons(dragon) => Tile::Dragon(dragon),
+            Yaku::SeatWind(wind) | Yaku::PrevalentWind(wind) => {
+                Tile::Wind(wind)
+            }
+            _ => unreachable!(),
+        };
+        push(
+            yaku,
+            restricted(&counts, &melds, &ANY, &[Set::Triplet(tile)]),
+        );
+    }
+
+    let dragons = [Dragon::Green, Dragon::Red, Dragon::White]
+        .map(|dragon| Set::Triplet(Tile::Dragon(dragon)));
+    push(
+        Yaku::BigThreeDragons,
+        restricted(&counts, &melds, &ANY, &dragons),
+    );
+
+    let straights = [Simple::Man, Simple::Pin, Simple::Sou].map(|suit| {
+        [1, 4, 7].map(|start| Set::Sequence(Tile::Simple(suit(start))))
+    });
+    push(
+        Yaku::PureStraight,
+        fastest(
+            straights
+                .iter()
+                .map(|sets| restricted(&counts, &melds, &ANY, sets)),
+        )
+        .unwrap_or(Reach::Impossible("with too many other calls")),
+    );
+
+    let mixed = |start| {
+        [Simple::Man, Simple::Pin, Simple::Sou]
+            .map(|suit| Set::Sequence(Tile::Simple(suit(start))))
+    };
+    push(
+        Yaku::MixedTripleSequence,
+        fastest(
+            (1..=7)
+                .map(|start| restricted(&counts, &melds, &ANY, &mixed(start))),
+        )
+        .unwrap_or(Reach::Impossible("with too many other calls")),
+    );
+
+    let triple = |number| {
+        [Simple::Man, Simple::Pin, Simple::Sou]
+            .map(|suit| Set::Triplet(Tile::Simple(suit(number))))
+    };
+    push(
+        Yaku::TripleTriplets,
+        fastest(
+            (1..=9).map(|number| {
+                restricted(&counts, &melds, &ANY, &triple(number))
+            }),
+        )
+        .unwrap_or(Reach::Impossible("with too many other calls")),
+    );
+
+    potentials.sort_by_key(|potential| match potential.reach {
+        Reach::Tiles(tiles) => (0, tiles),
+        Reach::Impossible(_) => (1, 0),
+    });
+    potentials
+}
+
+/// the han a hand can end up with from its yakus: the cheapest of the
+/// fastest yakus up to the most valuable reachable one. none when every
+/// yaku is ruled out.
+pub fn han_range(potentials: &[YakuPotential]) -> Option<(u8, u8)> {
+    let reachable: Vec<(u8, u8)> = potentials
+        .iter()
+        .filter_map(|potential| match potential.reach {
+            Reach::Tiles(tiles) if potential.han > 0 => {
+                Some((tiles, potential.han))
+            }
+            _ => None,
+        })
+        .collect();
+    let fastest = reachable.iter().map(|(tiles, _)| *tiles).min()?;
+    let low = reachable
+        .iter()
+        .filter(|(tiles, _)| *tiles == fastest)
+        .map(|(_, han)| *han)
+        .min()?;
+    let high = reachable.iter().map(|(_, han)| *han).max()?;
+    Some((low, high))
+}
+
+fn tiles(shanten: i8) -> Reach {
+    Reach::Tiles((shanten + 1).max(0) as u8)
+}
+
+/// the fastest of several ways to the same yaku, none when all of them are
+/// impossible.
+fn fastest(reaches: impl Iterator<Item = Reach>) -> Option<Reach> {
+    reaches
+        .filter_map(|reach| match reach {
+            Reach::Tiles(tiles) => Some(tiles),
+            Reach::Impossible(_) => None,
+        })
+        .min()
+        .map(Reach::Tiles)
+}
+
+/// the shanten of a hand that may only use the tiles and set kinds of
+/// `restriction` and has to contain the `required` sets. tiles the hand
+/// doesn't have for a required set count as tiles to exchange.
+fn restricted(
+    counts: &[u8; TILE_KINDS],
+    melds: &[Set],
+    restriction: &Restriction,
+    required: &[Set],
+) -> Reach {
+    let fits = |set: &Set| {
+        set.tiles()
+            .iter()
+            .all(|tile| (restriction.allowed)(tile.index().unwrap_or_default()))
+            && (restriction.sequences || matches!(set, Set::Triplet(_)))
+    };
+    if !melds.iter().all(fits) {
+        return Reach::Impossible(restriction.reason);
+    }
+
+    let required: Vec<&Set> =
+        required.iter().filter(|set| !melds.contains(set)).collect();
+    if melds.len() + required.len() > 4 {
+        return Reach::Impossible("with too many other calls");
+    }
+
+    let mut counts = *counts;
+    let mut missing = 0;
+    for set in &required {
+        for tile in set.tiles() {
+            let index = tile.index().unwrap_or_default();
+            if counts[index] > 0 {
+                counts[index] -= 1;
+            } else {
+                missing += 1;
+            }
+        }
+    }
+
+    let mut combined = [[-1; 5]; 2];
+    combined[0][0] = 0;
+    for start in [0, 9, 18, 27] {
+        let mut suit = [0; 9];
+        for (offset, count) in suit.iter_mut().enumerate() {
+            let index = start + offset;
+            if index < TILE_KINDS && (restriction.allowed)(index) {
+                *count = counts[index];
+            }
+        }
+        let sequences = restriction.sequences && start < 27;
+        combined = combine(&combined, &suit_blocks(&suit, sequences));
+    }
+
+    tiles(shanten_of_blocks(&combined, melds.len() + required.len()) + missing)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        player::Player,
+        potential::{han_range, potential, Reach},
+        tile::{tiles, Dragon, Wind},
+        yaku::Yaku,
+    };
+
+    fn reach(player: &Player, yaku: Yaku) -> Reach {
+        potential(player, Wind::East)
+            .into_iter()
+            .find(|potential| potential.yaku == yaku)
+            .unwrap()
+            .reach
+    }
+
+    #[test]
+    fn closed_hand() {
+        let player = Player::new(
+            Wind::South,
+            tiles("M1 M2 M3 M5 M7 M8 M9 P2 P4 S5 S6 RD RD"),
+        );
+
+        // 1 shanten, two tiles to win.
+        assert_eq!(reach(&player, Yaku::Riichi), Reach::Tiles(2));
+        // the pin and sou tiles have to go, and one more man tile.
+        assert_eq!(reach(&player, Yaku::HalfFlush), Reach::Tiles(4));
+        assert_eq!(reach(&player, Yaku::Dragons(Dragon::Red)), Reach::Tiles(3));
+        assert_eq!(reach(&player, Yaku::PureStraight), Reach::Tiles(3));
+
+        let (low, high) = han_range(&potential(&player, Wind::East)).unwrap();
+        assert_eq!(low, 1);
+        assert_eq!(high, 13);
+    }
+
+    #[test]
+    fn calls_rule_out_yakus() {
+        let player = Player {
+            dealt_in: tiles("S2 S3 S4"),
+            ..Player::new(Wind::South, tiles("M1 M2 M3 M5 M6 P2 P2 P4 S5 S6"))
+        };
+
+        assert!(matches!(
+            reach(&player, Yaku::AllTriplets),
+            Reach::Impossible(_)
+        ));
+        assert!(matches!(reach(&player, Yaku::Riichi), Reach::Impossible(_)));
+        assert!(matches!(reach(&player, Yaku::AllSimples), Reach::Tiles(_)));
+        // the called sou sequence still allows a sou flush.
+        assert!(matches!(reach(&player, Yaku::FullFlush), Reach::Tiles(_)));
+        assert_eq!(
+            reach(&player, Yaku::AllHonors).to_string(),
+            "impossible after calling a suit"
+        );
+
+        let potentials = potential(&player, Wind::East);
+        assert!(potentials
+            .iter()
+            .all(|potential| potential.han == potential.yaku.han(false)));
+    }
+}
@@ -0,0 +1,338 @@
+use crate::{
+    efficiency::estimate_value,
+    player::Player,
+    score::{reading, Score},
+    tile::{Tile, Wind, TILE_KINDS},
+    yaku::Yaku,
+};
+
+/// the riichi deposit.
+pub const RIICHI_STICK: i32 = 1000;
+/// riichi can't be declared with fewer tiles left in the live wall.
+pub const MIN_WALL_FOR_RIICHI: usize = 4;
+
+/// opponents fold against a riichi, so fewer of the winning tiles come out
+/// as discards. these are the number of tiles seen per own draw, own draw
+/// included, with and without a riichi: a rough model, not a measurement.
+const TILES_SEEN_DAMA: f32 = 2.0;
+const TILES_SEEN_RIICHI: f32 = 1.5;
+/// the share of wins by tsumo with and without a riichi.
+const TSUMO_SHARE_DAMA: f32 = 0.35;
+const TSUMO_SHARE_RIICHI: f32 = 0.5;
+
+/// where the player stands in the game.
+#[derive(Debug, Clone, Copy)]
+pub struct Situation {
+    pub dealer: bool,
+    pub score: i32,
+    /// the player's score minus the best of the others, negative when
+    /// behind.
+    pub lead: i32,
+    /// the last round of the game, where placement matters more than
+    /// points.
+    pub all_last: bool,
+    pub wall_remaining: usize,
+}
+
+impl Situation {
+    /// a non-dealer at the start of an east round on 25000 points.
+    pub fn new() -> Situation {
+        Situation {
+            dealer: false,
+            score: 25000,
+            lead: 0,
+            all_last: false,
+            wall_remaining: 70,
+        }
+    }
+}
+
+impl Default for Situation {
+    fn default() -> Self {
+        Situation::new()
+    }
+}
+
+/// what one way of playing a tenpai hand is expected to bring.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Expectation {
+    /// the average points of a win, weighted by the unseen winning tiles.
+    pub points: f32,
+    pub win_chance: f32,
+    /// the points won on average, less the riichi stick when it's lost.
+    pub expected: f32,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RiichiAdvice {
+    pub riichi: bool,
+    /// the unseen copies of the winning tiles.
+    pub wait_tiles: u8,
+    pub dama: Expectation,
+    pub with_riichi: Expectation,
+    pub explanation: String,
+}
+
+/// compares declaring riichi with staying silent for the player's closed
+/// tenpai hand of 13 tiles. none when the hand is open or not tenpai.
+pub fn advise_riichi(
+    player: &Player,
+    unseen: &[u8; TILE_KINDS],
+    dora_indicators: &[Tile],
+    prevalent_wind: Wind,
+    situation: &Situation,
+) -> Option<RiichiAdvice> {
+    let waits = player.waits();
+    if !player.is_menzenchin() || waits.is_empty() {
+        return None;
+    }
+
+    let doras: Vec<Tile> = dora_indicators
+        .iter()
+        .map(|indicator| indicator.indicate_dora())
+        .collect();
+    let unseen_total = unseen.iter().map(|count| *count as u32).sum::<u32>();
+    let ura_indicators = dora_indicators.len().max(1) as f32;
+
+    let mut wait_tiles = 0;
+    let mut dama_points = 0.0;
+    let mut riichi_points = 0.0;
+    let mut dama_yaku = true;
+    for wait in &waits {
+        let copies = unseen[wait.tile.index().unwrap_or_default()];
+        if copies == 0 {
+            continue;
+        }
+        wait_tiles += copies;
+
+        let mut complete = player.clone();
+        complete.hand.push(wait.tile);
+        let value = estimate_value(&complete, &doras, prevalent_wind);
+        let mut yakus: Vec<Yaku> = value
+            .yakus
+            .iter()
+            .copied()
+            .filter(|yaku| *yaku != Yaku::Riichi)
+            .collect();
+        let ron = reading(&complete, wait.tile, false, prevalent_wind);
+        let tsumo = reading(&complete, wait.tile, true, prevalent_wind);
+        // a pinfu wait is read the same way on a ron and on a tsumo.
+        if ron.is_some_and(|reading| reading.pinfu) {
+            yakus.push(Yaku::Pinfu);
+        }
+        let han = yakus.iter().map(|yaku| yaku.han(true)).sum::<u8>();
+        let ron_fu = ron.map_or(0, |reading| reading.fu);
+        let tsumo_fu = tsumo.map_or(0, |reading| reading.fu);
+
+        // the chance of an ura dora on the completed hand, per indicator.
+        let ura = (0..TILE_KINDS)
+            .map(|index| {
+                let indicated = Tile::from_index(index).indicate_dora();
+                let held =
+                    complete.hand.iter().filter(|tile| **tile == indicated);
+                unseen[index] as f32 * held.count() as f32
+            })
+            .sum::<f32>()
+            / unseen_total.max(1) as f32
+            * ura_indicators;
+        // winning within the first go-around, about four tiles.
+        let ippatsu = 1.0 - (1.0 - copies as f32 / unseen_total as f32).powi(4);
+
+        let points = |han: u8, tsumo_share: f32| {
+            let ron =
+                Score::new(han + value.dora, ron_fu).ron(situation.dealer);
+            let tsumo = Score::new(han + value.dora + 1, tsumo_fu)
+                .tsumo(situation.dealer)
+                .total(situation.dealer);
+            ron as f32 * (1.0 - tsumo_share) + tsumo as f32 * tsumo_share
+        };
+
+        let dama = if han == 0 {
+            dama_yaku = false;
+            // only a tsumo, worth its fully concealed hand.
+            points(0, 1.0) * TSUMO_SHARE_DAMA
+        } else {
+            points(han, TSUMO_SHARE_DAMA)
+        };
+        // ura dora and ippatsu as a chance of one more han.
+        let extra = (ura + ippatsu).min(1.0);
+        let riichi = points(han + 1, TSUMO_SHARE_RIICHI) * (1.0 - extra)
+            + points(han + 2, TSUMO_SHARE_RIICHI) * extra;
+
+        dama_points += dama * copies as f32;
+        riichi_points += riichi * copies as f32;
+    }
+
+    let copies = wait_tiles.max(1) as f32;
+    let draws = (situation.wall_remaining / 4) as f32;
+    let chance = |seen: f32| {
+        1.0 - (1.0 - wait_tiles as f32 / unseen_total.max(1) as f32)
+            .powf(draws * seen)
+    };
+
+    let dama = Expectation {
+        points: dama_points / copies,
+        win_chance: chance(TILES_SEEN_DAMA),
+        expected: dama_points / copies * chance(TILES_SEEN_DAMA),
+    };
+    let riichi_chance = chance(TILES_SEEN_RIICHI);
+    let with_riichi = Expectation {
+        points: riichi_points / copies,
+        win_chance: riichi_chance,
+        expected: riichi_points / copies * riichi_chance
+            - (1.0 - riichi_chance) * RIICHI_STICK as f32,
+    };
+
+    let (riichi, reason) = if situation.score < RIICHI_STICK {
+        (
+            false,
+            "there are no points left for the riichi stick".to_string(),
+        )
+    } else if situation.wall_remaining < MIN_WALL_FOR_RIICHI {
+        (false, "the wall is too short to declare riichi".to_string())
+    } else if wait_tiles == 0 {
+        (false, "every winning tile is already out".to_string())
+    } else if !dama_yaku {
+        (
+            true,
+            "without riichi the hand has no yaku for a ron".to_string(),
+        )
+    } else if situation.all_last && situation.lead > 0 {
+        (false, "any win ends the game in first".to_string())
+    } else if situation.all_last
+        && (dama.points as i32) < -situation.lead
+        && (with_riichi.points as i32) >= -situation.lead
+    {
+        (
+            true,
+            "only the riichi value is enough to take first".to_string(),
+        )
+    } else if with_riichi.expected > dama.expected {
+        (
+            true,
+            format!(
+                "riichi is worth {:.0} points more on average",
+                with_riichi.expected - dama.expected
+            ),
+        )
+    } else {
+        (
+            false,
+            format!(
+                "staying silent is worth {:.0} points more on average",
+                dama.expected - with_riichi.expected
+            ),
+        )
+    };
+
+    let explanation = format!(
+        "{}: {} tiles wait, dama {:.0} points at {:.0}%, riichi {:.0} points at {:.0}%",
+        if riichi { "riichi" } else { "dama" },
+        wait_tiles,
+        dama.points,
+        dama.win_chance * 100.0,
+        with_riichi.points,
+        with_riichi.win_chance * 100.0,
+    ) + ", "
+        + &reason;
+
+    Some(RiichiAdvice {
+        riichi,
+        wait_tiles,
+        dama,
+        with_riichi,
+        explanation,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        efficiency::unseen,
+        player::Player,
+        riichi::{advise_riichi, Situation},
+        tile::{tiles, Wind},
+    };
+
+    #[test]
+    fn advice() {
+        let situation = Situation::new();
+
+        // no yaku without riichi.
+        let player = Player::new(
+            Wind::South,
+            tiles("M1 M2 M3 M7 M8 M9 P1 P2 P3 S4 S5 EW EW"),
+        );
+        let hidden = unseen(&player.hand);
+        let advice =
+            advise_riichi(&player, &hidden, &[], Wind::East, &situation)
+                .unwrap();
+        assert!(advice.riichi);
+        assert_eq!(advice.wait_tiles, 8);
+        assert!(advice.explanation.contains("no yaku"));
+
+        // all simples and two dora, riichi adds at least a han.
+        let player = Player::new(
+            Wind::South,
+            tiles("M2 M3 M4 P5 P6 P7 S3 S4 S5 S6 S7 P8 P8"),
+        );
+        let dora_indicators = tiles("P7");
+        let hidden = unseen(&player.hand);
+        let advice = advise_riichi(
+            &player,
+            &hidden,
+            &dora_indicators,
+            Wind::East,
+            &situation,
+        )
+        .unwrap();
+        assert_eq!(advice.wait_tiles, 11);
+        assert!(advice.dama.points >= 3900.0);
+        assert!(advice.dama.win_chance > advice.with_riichi.win_chance);
+        assert!(advice.with_riichi.points > advice.dama.points);
+
+        // leading in the last round, any win will do.
+        let leading = Situation {
+            all_last: true,
+            lead: 5000,
+            ..situation
+        };
+        let advice = advise_riichi(
+            &player,
+            &hidden,
+            &dora_indicators,
+            Wind::East,
+            &leading,
+        )
+        .unwrap();
+        assert!(!advice.riichi);
+
+        let broke = Situation {
+            score: 500,
+            ..situation
+        };
+        let advice =
+            advise_riichi(&player, &hidden, &[], Wind::East, &broke).unwrap();
+        assert!(!advice.riichi);
+
+        // pinfu is a yaku of its own when staying silent.
+        let player = Player::new(
+            Wind::South,
+            tiles("M2 M3 M4 P5 P6 P7 S3 S4 S5 M7 M8 P9 P9"),
+        );
+        let hidden = unseen(&player.hand);
+        let advice =
+            advise_riichi(&player, &hidden, &[], Wind::East, &situation)
+                .unwrap();
+        assert!(!advice.explanation.contains("no yaku"));
+        assert!(advice.dama.points >= 1000.0);
+
+        // not tenpai.
+        let player = Player::new(
+            Wind::South,
+            tiles("M1 M2 M3 M7 M8 M9 P1 P2 P3 S4 S5 EW NW"),
+        );
+        assert!(advise_riichi(&player, &hidden, &[], Wind::East, &situation)
+            .is_none());
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+};
+
+/// a small seeded pseudo random number generator (splitmix64). the same seed
+/// always gives the same sequence, which is what makes shuffles repeatable.
+/// it's fast and well distributed but not meant to be unpredictable.
+#[derive(Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// a uniformly distributed number in `0..bound`.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0);
+        // rejects the top of the range that would make lower numbers more
+        // likely.
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+
+    pub fn roll_die(&mut self) -> u8 {
+        self.below(6) as u8 + 1
+    }
+
+    /// fisher-yates shuffle.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            let other = self.below(index as u64 + 1) as usize;
+            items.swap(index, other);
+        }
+    }
+}
+
+/// 64 bits from the random keys the standard library seeds its hash maps
+/// with from the operating system. unlike the time, nobody can guess them.
+pub fn entropy() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rng::{entropy, Rng};
+
+    #[test]
+    fn reproducible() {
+        // reference values of splitmix64 seeded with 0.
+        let mut rng = Rng::new(0);
+        assert_eq!(rng.next_u64(), 0xe220_a839_7b1d_cdaf);
+        assert_eq!(rng.next_u64(), 0x6e78_9e6a_a1b9_65f4);
+
+        let mut first: Vec<u8> = (0..50).collect();
+        let mut second = first.clone();
+        Rng::new(42).shuffle(&mut first);
+        Rng::new(42).shuffle(&mut second);
+        assert_eq!(first, second);
+        assert_ne!(first, (0..50).collect::<Vec<u8>>());
+
+        let mut rng = Rng::new(7);
+        for _ in 0..100 {
+            assert!((1..=6).contains(&rng.roll_die()));
+            assert!(rng.below(3) < 3);
+        }
+
+        assert_ne!(entropy(), entropy());
+    }
+}
@@ -0,0 +1,2022 @@
+use std::{
+    fmt::Display,
+    mem::discriminant,
+    sync::mpsc::{channel, Receiver, Sender},
+};
+
+use crate::{
+    call::Call,
+    decompose::Set,
+    event::GameEvent,
+    furiten::FuritenTracker,
+    player::{Player, WinContext},
+    riichi::{MIN_WALL_FOR_RIICHI, RIICHI_STICK},
+    ruleset::{MultipleRon, Ruleset},
+    score::{fu, Score},
+    tile::{Tile, Wind},
+    wait::waits,
+    wall::Wall,
+    yaku::Yaku,
+};
+
+/// the tiles in a hand waiting for a draw.
+pub const HAND_SIZE: usize = 13;
+/// no more kans can be declared once there are this many on the table.
+pub const MAX_KANS: usize = 4;
+const SEATS: [Wind; 4] = [Wind::East, Wind::South, Wind::West, Wind::North];
+
+/// what the round is waiting for.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Phase {
+    /// the player on turn has to draw.
+    Draw,
+    /// the player on turn holds a drawn tile and has to discard or win.
+    Discard,
+    /// the player on turn discarded and the others may claim the tile.
+    Claims,
+    Ended,
+}
+
+/// what a player can claim another player's discard for.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Claim {
+    Ron,
+    Call(Call),
+}
+
+impl Display for Claim {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Claim::Ron => write!(f, "ron"),
+            Claim::Call(call) => write!(f, "{}", call),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RoundError {
+    Ended,
+    /// the action doesn't fit what the round is waiting for.
+    WrongPhase(Phase),
+    NotInHand(Tile),
+    NotComplete,
+    NoYaku,
+    /// the player can't claim the discard this way.
+    CantClaim(Claim),
+    CantRiichi(&'static str),
+    /// a player in riichi can only discard the tile they drew.
+    RiichiLocked,
+    CantKan(Tile),
+    NotNineTerminals,
+    /// only in sanma, after a draw with a north in the hand, or the drawn
+    /// north in riichi.
+    CantNukidora,
+    /// discarding the tile right after the call would swap-call it, see
+    /// `Kuikae`.
+    Kuikae(Tile),
+}
+
+impl Display for RoundError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RoundError::Ended => write!(f, "the round is over"),
+            RoundError::WrongPhase(phase) => {
+                write!(f, "not possible while waiting for {:?}", phase)
+            }
+            RoundError::NotInHand(tile) => {
+                write!(f, "{} isn't in the hand", tile)
+            }
+            RoundError::NotComplete => write!(f, "the hand isn't complete"),
+            RoundError::NoYaku => write!(f, "the hand has no yaku"),
+            RoundError::CantClaim(claim) => {
+                write!(f, "the discard can't be claimed for {}", claim)
+            }
+            RoundError::CantRiichi(reason) => {
+                write!(f, "riichi isn't possible, {}", reason)
+            }
+            RoundError::RiichiLocked => {
+                write!(f, "the hand is locked in riichi")
+            }
+            RoundError::CantKan(tile) => write!(f, "{} can't be a kan", tile),
+            RoundError::NotNineTerminals => {
+                write!(f, "the round can't be aborted for nine terminals")
+            }
+            RoundError::CantNukidora => {
+                write!(f, "no north can be set aside as nukidora")
+            }
+            RoundError::Kuikae(tile) => {
+                write!(f, "{} can't be discarded right after the call", tile)
+            }
+        }
+    }
+}
+
+/// a player's riichi, kept for the yakus depending on when it was made.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Declaration {
+    /// the index of the declaring discard in the player's river.
+    pub discard: usize,
+    /// declared on the player's first discard before any call or kan.
+    pub double: bool,
+    /// a win now would be ippatsu: the player hasn't discarded again and
+    /// nobody called or declared a kan since.
+    pub ippatsu: bool,
+    /// the stick is on the table, which only happens once the declaring
+    /// discard wasn't won on.
+    pub stick: bool,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Win {
+    pub winner: usize,
+    /// the player who dealt in, none for a tsumo.
+    pub discarder: Option<usize>,
+    pub yakus: Vec<Yaku>,
+    /// the han include the dora.
+    pub score: Score,
+}
+
+/// a round ending early without a winner.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AbortiveDraw {
+    /// kyuushu kyuuhai, a player declared nine different terminals and
+    /// honors on their first draw.
+    NineTerminals,
+    /// suufon renda, everyone discarded the same wind on the first
+    /// go-around.
+    FourWinds,
+    /// suucha riichi, every player declared riichi.
+    FourRiichi,
+    /// suukaikan, four kans by more than one player.
+    FourKans,
+    /// three players called ron on the same discard.
+    TripleRon,
+}
+
+impl Display for AbortiveDraw {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AbortiveDraw::NineTerminals => write!(f, "nine terminals"),
+            AbortiveDraw::FourWinds => write!(f, "four winds"),
+            AbortiveDraw::FourRiichi => write!(f, "four riichi"),
+            AbortiveDraw::FourKans => write!(f, "four kans"),
+            AbortiveDraw::TripleRon => write!(f, "triple ron"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum RoundEnd {
+    /// the winners in turn order after the discarder, several only when
+    /// they won on the same discard.
+    Win(Vec<Win>),
+    /// the live wall ran out, with the table positions of the players who
+    /// were tenpai and any nagashi mangan.
+    ExhaustiveDraw {
+        tenpai: Vec<usize>,
+        nagashi: Vec<Win>,
+    },
+    AbortiveDraw(AbortiveDraw),
+}
+
+/// a discard someone called.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Called {
+    pub caller: usize,
+    pub discarder: usize,
+    pub call: Call,
+    pub tile: Tile,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RoundResult {
+    pub end: RoundEnd,
+    /// what each player won or lost, by table position. a winner taking
+    /// the riichi sticks off the table makes them add up to more than 0.
+    pub payments: Vec<i32>,
+    /// the dealer won, was tenpai at the end of the wall or the round was
+    /// aborted, so they deal again.
+    pub dealer_keeps: bool,
+}
+
+/// one hand from the deal to a win or the end of the wall. players sit at
+/// table positions in turn order, the dealer's being east, and the round
+/// moves forward through `draw`, `discard` and `tsumo`, with `claim` and
+/// `resolve` after every discard.
+#[derive(Clone)]
+pub struct Round {
+    pub ruleset: Ruleset,
+    pub players: Vec<Player>,
+    pub scores: Vec<i32>,
+    pub wall: Wall,
+    pub prevalent_wind: Wind,
+    pub dealer: usize,
+    /// the table position of the player on turn.
+    pub turn: usize,
+    pub phase: Phase,
+    /// the tile the player on turn drew, which a tsumo wins on.
+    pub drawn: Option<Tile>,
+    /// whether `drawn` came from the dead wall after a kan or nukidora.
+    pub after_kan: bool,
+    /// by table position.
+    pub furiten: Vec<FuritenTracker>,
+    /// the claims on the last discard so far, by table position.
+    pub claims: Vec<(usize, Claim)>,
+    /// every call in the round so far.
+    pub calls: Vec<Called>,
+    /// the tiles the player on turn may not discard after their call.
+    pub kuikae: Vec<Tile>,
+    /// by table position, the player liable for a big three dragons or
+    /// four big winds win for feeding the final set.
+    pub pao: Vec<Option<usize>>,
+    /// by table position.
+    pub declarations: Vec<Option<Declaration>>,
+    /// the riichi sticks on the table, which go to the next winner.
+    pub riichi_sticks: u32,
+    /// the repeat counter, each adding `Ruleset::honba_bonus` to a win.
+    pub honba: u32,
+    pub result: Option<RoundResult>,
+    /// everything that happened in the round so far, from the deal on.
+    pub events: Vec<GameEvent>,
+    /// the senders of `subscribe`. a clone of the round keeps sending to
+    /// the same subscribers.
+    pub subscribers: Vec<Sender<GameEvent>>,
+}
+
+impl Round {
+    /// deals a hand to each of the players whose `scores` are given, four
+    /// tiles at a time and then one, starting with the dealer.
+    pub fn new(
+        ruleset: Ruleset,
+        mut wall: Wall,
+        prevalent_wind: Wind,
+        dealer: usize,
+        scores: Vec<i32>,
+    ) -> Round {
+        let count = scores.len();
+        let mut players: Vec<Player> = (0..count)
+            .map(|position| {
+                Player::new(SEATS[(position + count - dealer) % count], vec![])
+            })
+            .collect();
+
+        for take in [4, 4, 4, 1] {
+            for offset in 0..count {
+                let player = &mut players[(dealer + offset) % count];
+                player.hand.extend((0..take).filter_map(|_| wall.draw()));
+            }
+        }
+        for player in players.iter_mut() {
+            while player.set_aside_bonus() > 0 {
+                let missing = HAND_SIZE - player.hand.len();
+                player.hand.extend(
+                    (0..missing).filter_map(|_| wall.draw_replacement()),
+                );
+            }
+        }
+
+        let deal = GameEvent::Deal {
+            dealer,
+            prevalent_wind,
+            hands: players.iter().map(|player| player.hand.clone()).collect(),
+            dora_indicator: wall.dora_indicators()[0],
+        };
+
+        Round {
+            ruleset,
+            players,
+            scores,
+            wall,
+            prevalent_wind,
+            dealer,
+            turn: dealer,
+            phase: Phase::Draw,
+            drawn: None,
+            after_kan: false,
+            furiten: vec![FuritenTracker::new(); count],
+            claims: vec![],
+            calls: vec![],
+            kuikae: vec![],
+            pao: vec![None; count],
+            declarations: vec![None; count],
+            riichi_sticks: 0,
+            honba: 0,
+            result: None,
+            events: vec![deal],
+            subscribers: vec![],
+        }
+    }
+
+    /// the player on turn.
+    pub fn current(&self) -> &Player {
+        &self.players[self.turn]
+    }
+
+    /// a stream of every event of the round, starting with those that
+    /// already happened. the subscription ends when the receiver is
+    /// dropped.
+    pub fn subscribe(&mut self) -> Receiver<GameEvent> {
+        let (sender, receiver) = channel();
+        self.add_subscriber(sender);
+        receiver
+    }
+
+    /// sends the events so far to `subscriber`, and then every new one.
+    /// false when its receiver is gone.
+    pub fn add_subscriber(&mut self, subscriber: Sender<GameEvent>) -> bool {
+        for event in &self.events {
+            if subscriber.send(event.clone()).is_err() {
+                return false;
+            }
+        }
+        self.subscribers.push(subscriber);
+        true
+    }
+
+    fn emit(&mut self, event: GameEvent) {
+        self.subscribers
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+        self.events.push(event);
+    }
+
+    /// the player on turn draws, setting flowers and seasons aside for a
+    /// replacement. none when the live wall is exhausted, which ends the
+    /// round.
+    pub fn draw(&mut self) -> Result<Option<Tile>, RoundError> {
+        self.expect(Phase::Draw)?;
+
+        let mut tile = self.wall.draw();
+        while let Some(bonus @ Tile::Bonus(_)) = tile {
+            self.players[self.turn].bonus.push(bonus);
+            self.emit(GameEvent::Draw {
+                position: self.turn,
+                tile: bonus,
+            });
+            tile = self.wall.draw_replacement();
+        }
+        let Some(tile) = tile else {
+            self.exhaustive_draw();
+            return Ok(None);
+        };
+
+        self.furiten[self.turn].next_turn();
+        self.players[self.turn].hand.push(tile);
+        self.drawn = Some(tile);
+        self.emit(GameEvent::Draw {
+            position: self.turn,
+            tile,
+        });
+        self.after_kan = false;
+        self.phase = Phase::Discard;
+        self.auto_discard();
+        Ok(Some(tile))
+    }
+
+    /// the player on turn declares riichi, discarding `tile` sideways. the
+    /// closed hand has to be tenpai after it, with the points for the stick
+    /// and enough tiles left in the wall for another draw.
+    pub fn declare_riichi(&mut self, tile: Tile) -> Result<(), RoundError> {
+        self.expect(Phase::Discard)?;
+        let player = &self.players[self.turn];
+        if player.riichi {
+            return Err(RoundError::CantRiichi(
+                "the hand is in riichi already",
+            ));
+        }
+        if !player.is_menzenchin() {
+            return Err(RoundError::CantRiichi("the hand is open"));
+        }
+        if self.scores[self.turn] < RIICHI_STICK {
+            return Err(RoundError::CantRiichi(
+                "there are no points for the stick",
+            ));
+        }
+        if self.wall.remaining() < MIN_WALL_FOR_RIICHI {
+            return Err(RoundError::CantRiichi("the wall is too short"));
+        }
+        let mut waiting = player.clone();
+        let position = waiting
+            .hand
+            .iter()
+            .position(|t| *t == tile)
+            .ok_or(RoundError::NotInHand(tile))?;
+        waiting.hand.remove(position);
+        if waiting.shanten() != 0 {
+            return Err(RoundError::CantRiichi("the hand isn't tenpai"));
+        }
+
+        let double = player.discarded.is_empty()
+            && self.players.iter().all(|player| {
+                player.dealt_in.is_empty() && player.kan.is_empty()
+            });
+        self.discard(tile)?;
+        self.players[self.turn].riichi = true;
+        self.declarations[self.turn] = Some(Declaration {
+            discard: self.players[self.turn].discarded.len() - 1,
+            double,
+            ippatsu: true,
+            stick: false,
+        });
+        self.emit(GameEvent::Riichi {
+            position: self.turn,
+            double,
+        });
+        Ok(())
+    }
+
+    /// the quads the player on turn can declare after a draw: four concealed
+    /// copies or a called triplet and its fourth tile. in riichi only a
+    /// concealed one of the drawn tile that leaves the waits as they are.
+    pub fn available_kans(&self) -> Vec<Tile> {
+        // right after a chi or pon the caller only discards.
+        let Some(drawn) = self.drawn else {
+            return vec![];
+        };
+        if self.phase != Phase::Discard
+            || self.wall.is_haitei()
+            || self.kans() >= MAX_KANS
+        {
+            return vec![];
+        }
+        let player = &self.players[self.turn];
+        let counts = Tile::counts(&player.hand);
+        let mut kans: Vec<Tile> = counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count == 4)
+            .map(|(index, _)| Tile::from_index(index))
+            .collect();
+
+        if !player.riichi {
+            // an added kan goes on a called triplet, not on three copies
+            // spread over sequences.
+            for set in player.meld_sets() {
+                if let Set::Triplet(tile) = set {
+                    let held =
+                        tile.index().is_some_and(|index| counts[index] > 0);
+                    if held && !player.kan.contains(&tile) {
+                        kans.push(tile);
+                    }
+                }
+            }
+            return kans;
+        }
+
+        let mut waiting = player.hand.clone();
+        if let Some(position) = waiting.iter().position(|t| *t == drawn) {
+            waiting.remove(position);
+        }
+        let before: Vec<Tile> = waits(&waiting, player.melds())
+            .iter()
+            .map(|wait| wait.tile)
+            .collect();
+        kans.retain(|tile| {
+            let after: Vec<Tile> =
+                player.hand.iter().copied().filter(|t| t != tile).collect();
+            let after: Vec<Tile> = waits(&after, player.melds() + 1)
+                .iter()
+                .map(|wait| wait.tile)
+                .collect();
+            *tile == drawn && after == before
+        });
+        kans
+    }
+
+    /// the player on turn declares a kan on `tile`, see `available_kans`,
+    /// turns over a new dora indicator and draws a replacement tile.
+    pub fn declare_kan(&mut self, tile: Tile) -> Result<(), RoundError> {
+        self.expect(Phase::Discard)?;
+        if !self.available_kans().contains(&tile) {
+            return Err(RoundError::CantKan(tile));
+        }
+        let player = &mut self.players[self.turn];
+        player.hand.retain(|t| *t != tile);
+        player.kan.push(tile);
+        for declaration in self.declarations.iter_mut().flatten() {
+            declaration.ippatsu = false;
+        }
+        self.emit(GameEvent::Kan {
+            position: self.turn,
+            discarder: None,
+            tile,
+        });
+        self.reveal_kan_dora();
+        self.replace()
+    }
+
+    /// whether the player on turn may set a north aside as nukidora, which
+    /// is only ever the drawn one in riichi.
+    pub fn can_declare_nukidora(&self) -> bool {
+        let north = Tile::Wind(Wind::North);
+        let player = &self.players[self.turn];
+        self.ruleset.is_sanma()
+            && self.phase == Phase::Discard
+            && self.drawn.is_some()
+            && !self.wall.is_haitei()
+            && player.hand.contains(&north)
+            && (!player.riichi || self.drawn == Some(north))
+    }
+
+    /// the player on turn sets a north aside, which counts as a dora, and
+    /// draws a replacement tile.
+    pub fn declare_nukidora(&mut self) -> Result<(), RoundError> {
+        self.expect(Phase::Discard)?;
+        if !self.can_declare_nukidora() {
+            return Err(RoundError::CantNukidora);
+        }
+        let north = Tile::Wind(Wind::North);
+        let player = &mut self.players[self.turn];
+        if let Some(position) = player.hand.iter().position(|t| *t == north) {
+            player.hand.remove(position);
+        }
+        player.bonus.push(north);
+        self.emit(GameEvent::Nukidora {
+            position: self.turn,
+        });
+        self.replace()
+    }
+
+    fn reveal_kan_dora(&mut self) {
+        if self.wall.reveal_kan_dora() {
+            if let Some(indicator) = self.wall.dora_indicators().last() {
+                self.emit(GameEvent::DoraReveal {
+                    indicator: *indicator,
+                });
+            }
+        }
+    }
+
+    /// the player on turn draws from the dead wall after a kan or nukidora.
+    fn replace(&mut self) -> Result<(), RoundError> {
+        let Some(replacement) = self.wall.draw_replacement() else {
+            self.exhaustive_draw();
+            return Ok(());
+        };
+        self.players[self.turn].hand.push(replacement);
+        self.drawn = Some(replacement);
+        self.after_kan = true;
+        self.emit(GameEvent::Draw {
+            position: self.turn,
+            tile: replacement,
+        });
+        self.phase = Phase::Discard;
+        self.auto_discard();
+        Ok(())
+    }
+
+    /// a player in riichi throws the tile they drew unless it wins, makes a
+    /// kan or is a nukidora.
+    fn auto_discard(&mut self) {
+        let Some(tile) = self.drawn else {
+            return;
+        };
+        if !self.players[self.turn].riichi
+            || self.win(self.turn, None, tile).is_ok()
+            || !self.available_kans().is_empty()
+            || self.can_declare_nukidora()
+        {
+            return;
+        }
+        let _ = self.discard(tile);
+    }
+
+    /// the player on turn discards `tile` into their river, for the others
+    /// to claim.
+    pub fn discard(&mut self, tile: Tile) -> Result<(), RoundError> {
+        self.expect(Phase::Discard)?;
+        let player = &mut self.players[self.turn];
+        if player.riichi && self.drawn != Some(tile) {
+            return Err(RoundError::RiichiLocked);
+        }
+        if self.kuikae.contains(&tile) {
+            return Err(RoundError::Kuikae(tile));
+        }
+        if let Some(declaration) = &mut self.declarations[self.turn] {
+            declaration.ippatsu = false;
+        }
+        let position = player
+            .hand
+            .iter()
+            .position(|t| *t == tile)
+            .ok_or(RoundError::NotInHand(tile))?;
+        player.hand.remove(position);
+        player.discarded.push(tile);
+
+        self.drawn = None;
+        self.kuikae.clear();
+        self.claims.clear();
+        self.phase = Phase::Claims;
+        self.emit(GameEvent::Discard {
+            position: self.turn,
+            tile,
+        });
+        Ok(())
+    }
+
+    /// the discard the others may claim.
+    pub fn discarded(&self) -> Option<Tile> {
+        if self.phase != Phase::Claims {
+            return None;
+        }
+        self.players[self.turn].discarded.last().copied()
+    }
+
+    /// every claim the player at `position` may make on the discard. chi
+    /// only on the discard of the player to their left, no calls on the
+    /// last tile or in riichi and none leaving only swap-call discards.
+    pub fn available_claims(&self, position: usize) -> Vec<Claim> {
+        let Some(tile) = self.discarded() else {
+            return vec![];
+        };
+        if position == self.turn {
+            return vec![];
+        }
+
+        let player = &self.players[position];
+        let mut claims = vec![];
+        if self.furiten[position].can_ron(player, tile)
+            && self.win(position, Some(self.turn), tile).is_ok()
+        {
+            claims.push(Claim::Ron);
+        }
+        if !self.wall.is_haitei() && !player.riichi {
+            let can_chi = !self.ruleset.is_sanma()
+                && position == (self.turn + 1) % self.players.len();
+            claims.extend(
+                Call::available(player, tile, can_chi)
+                    .into_iter()
+                    .filter(|call| *call != Call::Kan || self.kans() < MAX_KANS)
+                    .filter(|call| {
+                        let forbidden =
+                            self.ruleset.kuikae.forbidden(*call, tile);
+                        let mut after = player.clone();
+                        call.apply(&mut after, tile);
+                        *call == Call::Kan
+                            || after.hand.iter().any(|t| !forbidden.contains(t))
+                    })
+                    .map(Claim::Call),
+            );
+        }
+        claims
+    }
+
+    /// the player at `position` claims the discard, replacing any claim
+    /// they made before.
+    pub fn claim(
+        &mut self,
+        position: usize,
+        claim: Claim,
+    ) -> Result<(), RoundError> {
+        self.expect(Phase::Claims)?;
+        if !self.available_claims(position).contains(&claim) {
+            return Err(RoundError::CantClaim(claim));
+        }
+        self.claims.retain(|(claimer, _)| *claimer != position);
+        self.claims.push((position, claim));
+        Ok(())
+    }
+
+    /// settles the claims on the discard: ron before pon and kan before
+    /// chi. a call makes the caller the player on turn, without claims the
+    /// turn passes on.
+    pub fn resolve(&mut self) -> Result<(), RoundError> {
+        self.expect(Phase::Claims)?;
+        let discarder = self.turn;
+        let count = self.players.len();
+        let Some(tile) = self.discarded() else {
+            return Err(RoundError::WrongPhase(self.phase));
+        };
+
+        let mut claims = std::mem::take(&mut self.claims);
+        claims.sort_by_key(|(position, _)| {
+            (position + count - discarder) % count
+        });
+        let rons: Vec<usize> = claims
+            .iter()
+            .filter(|(_, claim)| *claim == Claim::Ron)
+            .map(|(position, _)| *position)
+            .collect();
+
+        for (position, player) in self.players.iter_mut().enumerate() {
+            if position != discarder && !rons.contains(&position) {
+                self.furiten[position].pass(player, tile);
+                if player.riichi {
+                    player.passed.push(tile);
+                }
+            }
+        }
+        if !rons.is_empty() {
+            return self.ron(discarder, tile, rons);
+        }
+
+        if let Some(declaration) = &mut self.declarations[discarder] {
+            if !declaration.stick {
+                declaration.stick = true;
+                self.scores[discarder] -= RIICHI_STICK;
+                self.riichi_sticks += 1;
+                let mut payments = vec![0; count];
+                payments[discarder] = -RIICHI_STICK;
+                self.emit(GameEvent::ScoreChange {
+                    payments,
+                    scores: self.scores.clone(),
+                });
+            }
+        }
+        if let Some(draw) = self.abortive_draw() {
+            self.end(RoundEnd::AbortiveDraw(draw), vec![0; count]);
+            return Ok(());
+        }
+
+        let call = claims
+            .iter()
+            .find(|(_, claim)| {
+                matches!(claim, Claim::Call(Call::Pon | Call::Kan))
+            })
+            .or_else(|| claims.first());
+        match call {
+            Some(&(caller, Claim::Call(call))) => {
+                self.call(discarder, caller, call, tile)
+            }
+            _ => {
+                self.turn = (discarder + 1) % count;
+                self.phase = Phase::Draw;
+                Ok(())
+            }
+        }
+    }
+
+    /// whether the player on turn may abort the round on their first draw
+    /// with nine different terminals and honors.
+    pub fn can_declare_nine_terminals(&self) -> bool {
+        let player = &self.players[self.turn];
+        let kinds = Tile::counts(&player.hand)
+            .iter()
+            .enumerate()
+            .filter(|(index, count)| {
+                **count > 0 && Tile::from_index(*index).is_terminal_or_honor()
+            })
+            .count();
+        self.ruleset.abortive_draws.nine_terminals
+            && self.phase == Phase::Discard
+            && self.drawn.is_some()
+            && player.discarded.is_empty()
+            && self.uninterrupted()
+            && kinds >= 9
+    }
+
+    pub fn declare_nine_terminals(&mut self) -> Result<(), RoundError> {
+        self.expect(Phase::Discard)?;
+        if !self.can_declare_nine_terminals() {
+            return Err(RoundError::NotNineTerminals);
+        }
+        self.end(
+            RoundEnd::AbortiveDraw(AbortiveDraw::NineTerminals),
+            vec![0; self.players.len()],
+        );
+        Ok(())
+    }
+
+    /// the abortive draw a discard nobody won on leads to, if the ruleset
+    /// plays with it.
+    fn abortive_draw(&self) -> Option<AbortiveDraw> {
+        let rules = self.ruleset.abortive_draws;
+        let first_discards: Vec<&Tile> = self
+            .players
+            .iter()
+            .filter(|player| player.discarded.len() == 1)
+            .map(|player| &player.discarded[0])
+            .collect();
+        let owners = self
+            .players
+            .iter()
+            .filter(|player| !player.kan.is_empty())
+            .count();
+
+        if rules.four_winds
+            && self.uninterrupted()
+            && first_discards.len() == self.players.len()
+            && matches!(first_discards[0], Tile::Wind(_))
+            && first_discards.iter().all(|tile| *tile == first_discards[0])
+        {
+            Some(AbortiveDraw::FourWinds)
+        } else if rules.four_riichi
+            && self
+                .declarations
+                .iter()
+                .all(|declaration| declaration.is_some())
+        {
+            Some(AbortiveDraw::FourRiichi)
+        } else if rules.four_kans && self.kans() >= MAX_KANS && owners > 1 {
+            Some(AbortiveDraw::FourKans)
+        } else {
+            None
+        }
+    }
+
+    /// nobody called or declared a kan yet.
+    fn uninterrupted(&self) -> bool {
+        self.calls.is_empty() && self.kans() == 0
+    }
+
+    /// the kans on the table.
+    fn kans(&self) -> usize {
+        self.players.iter().map(|player| player.kan.len()).sum()
+    }
+
+    /// settles the rons on `tile` by the ruleset's `MultipleRon`, `winners`
+    /// in turn order.
+    fn ron(
+        &mut self,
+        discarder: usize,
+        tile: Tile,
+        winners: Vec<usize>,
+    ) -> Result<(), RoundError> {
+        let winners = match self.ruleset.multiple_ron {
+            MultipleRon::HeadBump => winners[..1].to_vec(),
+            MultipleRon::TripleRonAbort if winners.len() >= 3 => {
+                self.end(
+                    RoundEnd::AbortiveDraw(AbortiveDraw::TripleRon),
+                    vec![0; self.players.len()],
+                );
+                return Ok(());
+            }
+            _ => winners,
+        };
+
+        let mut wins = vec![];
+        let mut payments = vec![0; self.players.len()];
+        for winner in winners {
+            let win = self.win(winner, Some(discarder), tile)?;
+            let points = win.score.ron(winner == self.dealer) as i32;
+            payments[winner] += points;
+            payments[discarder] -= points;
+            // the liable player pays half of the yakuman.
+            if let Some((liable, score)) = self.liability(&win) {
+                let half = score.ron(winner == self.dealer) as i32 / 2;
+                payments[discarder] += half;
+                payments[liable] -= half;
+            }
+            wins.push(win);
+        }
+        for win in &wins {
+            self.players[win.winner].hand.push(tile);
+        }
+        self.end(RoundEnd::Win(wins), payments);
+        Ok(())
+    }
+
+    /// moves the called discard into the caller's melds. a kan draws its
+    /// replacement tile and turns over a new dora indicator.
+    fn call(
+        &mut self,
+        discarder: usize,
+        caller: usize,
+        call: Call,
+        tile: Tile,
+    ) -> Result<(), RoundError> {
+        if !call.apply(&mut self.players[caller], tile) {
+            return Err(RoundError::CantClaim(Claim::Call(call)));
+        }
+        let player = &mut self.players[discarder];
+        player.discarded.pop();
+        player.called.push(tile);
+        self.calls.push(Called {
+            caller,
+            discarder,
+            call,
+            tile,
+        });
+        for declaration in self.declarations.iter_mut().flatten() {
+            declaration.ippatsu = false;
+        }
+        self.kuikae = self.ruleset.kuikae.forbidden(call, tile);
+        if self.ruleset.pao && matches!(call, Call::Pon | Call::Kan) {
+            // the called sets of the same kind as the tile, counting it.
+            let same_kind = self.players[caller]
+                .meld_sets()
+                .iter()
+                .filter(|set| {
+                    matches!(set, Set::Triplet(t)
+                        if discriminant(t) == discriminant(&tile))
+                })
+                .count();
+            let final_set = match tile {
+                Tile::Dragon(_) => same_kind == 3,
+                Tile::Wind(_) => same_kind == 4,
+                _ => false,
+            };
+            if final_set {
+                self.pao[caller] = Some(discarder);
+            }
+        }
+        self.turn = caller;
+        self.phase = Phase::Discard;
+        self.emit(match call {
+            Call::Chi(start) => GameEvent::Chi {
+                caller,
+                discarder,
+                tile,
+                start,
+            },
+            Call::Pon => GameEvent::Pon {
+                caller,
+                discarder,
+                tile,
+            },
+            Call::Kan => GameEvent::Kan {
+                position: caller,
+                discarder: Some(discarder),
+                tile,
+            },
+        });
+
+        if call == Call::Kan {
+            self.reveal_kan_dora();
+            return self.replace();
+        }
+        Ok(())
+    }
+
+    /// the player on turn wins on the tile they drew.
+    pub fn tsumo(&mut self) -> Result<&RoundResult, RoundError> {
+        self.expect(Phase::Discard)?;
+        let tile = self.drawn.ok_or(RoundError::NotComplete)?;
+        let winner = self.turn;
+        let win = self.win(winner, None, tile)?;
+
+        let mut payments = self.tsumo_payments(winner, win.score);
+        // the liable player pays the whole yakuman, as for a ron.
+        if let Some((liable, score)) = self.liability(&win) {
+            let shares = self.tsumo_payments(winner, score);
+            for (paid, share) in payments.iter_mut().zip(shares) {
+                *paid -= share;
+            }
+            payments[liable] -= score.ron(winner == self.dealer) as i32;
+            payments[winner] = 0;
+            payments[winner] = -payments.iter().sum::<i32>();
+        }
+        self.end(RoundEnd::Win(vec![win]), payments);
+        Ok(self.result.as_ref().unwrap())
+    }
+
+    /// the yakus and score of `winner`'s hand completed by `tile`, which is
+    /// already in it for a tsumo.
+    fn win(
+        &self,
+        winner: usize,
+        discarder: Option<usize>,
+        tile: Tile,
+    ) -> Result<Win, RoundError> {
+        let mut player = self.players[winner].clone();
+        if discarder.is_some() {
+            player.hand.push(tile);
+        }
+        if !player.is_complete() {
+            return Err(RoundError::NotComplete);
+        }
+
+        let tsumo = discarder.is_none();
+        let context = WinContext {
+            prevalent_wind: self.prevalent_wind,
+            tsumo,
+            last_tile: self.wall.is_haitei(),
+            after_kan: self.after_kan,
+            winning_tile: Some(tile),
+        };
+        let mut yakus = player.evaluate_win(&context);
+        // the bonus tiles count on top of any yaku, a declared riichi too.
+        let bonus = player.bonus_yakus();
+        yakus.retain(|yaku| !bonus.contains(yaku));
+        if let Some(declaration) = &self.declarations[winner] {
+            // riichi is a yaku on its own here.
+            yakus.retain(|yaku| *yaku != Yaku::Riichi);
+            yakus.push(if declaration.double {
+                Yaku::DoubeRiichi
+            } else {
+                Yaku::Riichi
+            });
+            if declaration.ippatsu {
+                yakus.push(Yaku::Ippatsu);
+            }
+        }
+        if yakus.is_empty() {
+            return Err(RoundError::NoYaku);
+        }
+        yakus.extend(bonus);
+        let menzenchin = player.is_menzenchin();
+        // a yakuman scores on its own, without the other yakus or dora.
+        let yakuman = yakus.iter().any(|yaku| yaku.han(menzenchin) >= 13);
+        if yakuman {
+            yakus.retain(|yaku| yaku.han(menzenchin) >= 13);
+        }
+
+        let mut doras = if yakuman {
+            vec![]
+        } else {
+            self.wall.doras(&self.ruleset)
+        };
+        if player.riichi && !yakuman {
+            doras.extend(
+                self.wall
+                    .ura_dora_indicators()
+                    .into_iter()
+                    .map(|indicator| self.ruleset.indicate_dora(indicator)),
+            );
+        }
+        // every north set aside is a dora of its own, on top of any dora
+        // indicator pointing to north.
+        let nukidora = if yakuman { 0 } else { player.nukidora() };
+        let tiles = [
+            player.hand.clone(),
+            player.meld_tiles(),
+            player.bonus.clone(),
+        ]
+        .concat();
+        let dora = doras
+            .iter()
+            .map(|dora| tiles.iter().filter(|tile| *tile == dora).count())
+            .sum::<usize>() as u8
+            + nukidora as u8;
+
+        let han = yakus.iter().map(|yaku| yaku.han(menzenchin)).sum::<u8>();
+        let fu = fu(&player, tile, tsumo, self.prevalent_wind).max(20);
+        Ok(Win {
+            winner,
+            discarder,
+            yakus,
+            score: Score::new(han + dora, fu),
+        })
+    }
+
+    /// settles the end of the wall. every player with a nagashi mangan is
+    /// paid as for a mangan tsumo, otherwise those who aren't tenpai pay
+    /// `Ruleset::noten_penalty` to those who are.
+    fn exhaustive_draw(&mut self) {
+        let count = self.players.len();
+        let tenpai: Vec<usize> = (0..count)
+            .filter(|position| {
+                self.players[*position]
+                    .waits()
+                    .iter()
+                    .any(|wait| !wait.impossible)
+            })
+            .collect();
+
+        let nagashi: Vec<Win> = (0..count)
+            .filter(|position| {
+                let discarded = &self.players[*position].discarded;
+                !discarded.is_empty()
+                    && discarded.iter().all(|tile| tile.is_terminal_or_honor())
+                    && !self
+                        .calls
+                        .iter()
+                        .any(|called| called.discarder == *position)
+            })
+            .map(|winner| Win {
+                winner,
+                discarder: None,
+                yakus: vec![Yaku::NagashiMangan],
+                score: Score::new(Yaku::NagashiMangan.han(true), 30),
+            })
+            .collect();
+
+        let mut payments = vec![0; count];
+        if !nagashi.is_empty() {
+            for win in &nagashi {
+                let paid = self.tsumo_payments(win.winner, win.score);
+                for (payment, paid) in payments.iter_mut().zip(paid) {
+                    *payment += paid;
+                }
+            }
+        } else if !tenpai.is_empty() && tenpai.len() < count {
+            let penalty = self.ruleset.noten_penalty;
+            let received = penalty / tenpai.len() as i32;
+            let paid = penalty / (count - tenpai.len()) as i32;
+            for (position, payment) in payments.iter_mut().enumerate() {
+                *payment = if tenpai.contains(&position) {
+                    received
+                } else {
+                    -paid
+                };
+            }
+        }
+
+        self.end(RoundEnd::ExhaustiveDraw { tenpai, nagashi }, payments);
+    }
+
+    /// the player liable for the big three dragons or four big winds of a
+    /// win by pao, and the score of that yakuman.
+    fn liability(&self, win: &Win) -> Option<(usize, Score)> {
+        let liable = self.pao[win.winner]?;
+        let yaku = win.yakus.iter().find(|yaku| {
+            matches!(yaku, Yaku::BigThreeDragons | Yaku::FourBigWinds)
+        })?;
+        Some((liable, Score::new(yaku.han(false), 0)))
+    }
+
+    /// what everyone pays `winner` for a tsumo worth `score`. in sanma the
+    /// missing non-dealer's share is lost, or split between the two payers
+    /// unless the ruleset plays with tsumo loss.
+    fn tsumo_payments(&self, winner: usize, score: Score) -> Vec<i32> {
+        let payment = score.tsumo(winner == self.dealer);
+        let split = if self.ruleset.is_sanma() && !self.ruleset.tsumo_loss {
+            (payment.non_dealer / 2).div_ceil(100) * 100
+        } else {
+            0
+        };
+        let mut payments = vec![0; self.players.len()];
+        for (position, paid) in payments.iter_mut().enumerate() {
+            if position == winner {
+                continue;
+            }
+            *paid = -((if position == self.dealer {
+                payment.dealer
+            } else {
+                payment.non_dealer
+            } + split) as i32);
+        }
+        payments[winner] = -payments.iter().sum::<i32>();
+        payments
+    }
+
+    fn end(&mut self, end: RoundEnd, mut payments: Vec<i32>) {
+        let dealer_keeps = match &end {
+            RoundEnd::Win(wins) => {
+                let winner = wins[0].winner;
+                let bonus = self.honba as i32 * self.ruleset.honba_bonus;
+                match wins[0].discarder {
+                    Some(discarder) => payments[discarder] -= bonus,
+                    None => {
+                        let others = payments.len() as i32 - 1;
+                        for (position, paid) in payments.iter_mut().enumerate()
+                        {
+                            if position != winner {
+                                *paid -= bonus / others;
+                            }
+                        }
+                    }
+                }
+                payments[winner] +=
+                    bonus + self.riichi_sticks as i32 * RIICHI_STICK;
+                self.riichi_sticks = 0;
+                wins.iter().any(|win| win.winner == self.dealer)
+            }
+            RoundEnd::ExhaustiveDraw { tenpai, .. } => {
+                tenpai.contains(&self.dealer)
+            }
+            RoundEnd::AbortiveDraw(_) => true,
+        };
+        for (score, paid) in self.scores.iter_mut().zip(&payments) {
+            *score += paid;
+        }
+        match &end {
+            RoundEnd::Win(wins) => {
+                for win in wins {
+                    self.emit(GameEvent::Win(win.clone()));
+                }
+            }
+            draw => self.emit(GameEvent::DrawGame(draw.clone())),
+        }
+        self.emit(GameEvent::ScoreChange {
+            payments: payments.clone(),
+            scores: self.scores.clone(),
+        });
+        self.phase = Phase::Ended;
+        self.result = Some(RoundResult {
+            end,
+            payments,
+            dealer_keeps,
+        });
+    }
+
+    fn expect(&self, phase: Phase) -> Result<(), RoundError> {
+        match self.phase {
+            Phase::Ended => Err(RoundError::Ended),
+            current if current != phase => Err(RoundError::WrongPhase(current)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        call::Call,
+        event::GameEvent,
+        rng::Rng,
+        round::{
+            AbortiveDraw, Called, Claim, Phase, Round, RoundEnd, RoundError,
+            HAND_SIZE,
+        },
+        ruleset::{Kuikae, MultipleRon, Ruleset},
+        tile::{tiles, Dragon, Tile, Wind, TILE_KINDS},
+        wall::Wall,
+        yaku::Yaku,
+    };
+
+    fn dealt(seed: u64, dealer: usize) -> Round {
+        let ruleset = Ruleset::riichi();
+        let wall = Wall::shuffled(&ruleset, &mut Rng::new(seed));
+        Round::new(ruleset, wall, Wind::East, dealer, vec![25000; 4])
+    }
+
+    /// the dealer at position 0 just discarded `tile` and the others hold
+    /// `hands`.
+    fn discarding(ruleset: Ruleset, tile: &str, hands: [&str; 3]) -> Round {
+        let mut round = dealt(1, 0);
+        round.ruleset = ruleset;
+        round.draw().unwrap();
+        let tile = tiles(tile)[0];
+        round.players[0].hand.push(tile);
+        for (player, hand) in round.players[1..].iter_mut().zip(hands) {
+            player.hand = tiles(hand);
+        }
+        round.discard(tile).unwrap();
+        round
+    }
+
+    #[test]
+    fn deal_and_play_out() {
+        let mut round = dealt(7, 1);
+        assert!(round
+            .players
+            .iter()
+            .all(|player| player.hand.len() == HAND_SIZE));
+        assert_eq!(round.players[1].seat, Wind::East);
+        assert_eq!(round.players[0].seat, Wind::North);
+        assert_eq!(round.turn, 1);
+        assert_eq!(round.wall.remaining(), 70);
+
+        let first = round.current().hand[0];
+        assert_eq!(
+            round.discard(first),
+            Err(RoundError::WrongPhase(Phase::Draw))
+        );
+
+        // everyone throws the tile they drew until the wall runs out.
+        while let Some(tile) = round.draw().unwrap() {
+            assert_eq!(round.current().hand.len(), HAND_SIZE + 1);
+            round.discard(tile).unwrap();
+            round.resolve().unwrap();
+        }
+        assert_eq!(round.phase, Phase::Ended);
+        let result = round.result.clone().unwrap();
+        let RoundEnd::ExhaustiveDraw { tenpai, nagashi } = &result.end else {
+            panic!("expected the wall to run out");
+        };
+        assert!(nagashi.is_empty());
+        let total: i32 = result.payments.iter().sum();
+        assert_eq!(total, 0);
+        assert_eq!(result.dealer_keeps, tenpai.contains(&1));
+        let discards: usize = round
+            .players
+            .iter()
+            .map(|player| player.discarded.len())
+            .sum();
+        assert_eq!(discards, 70);
+        assert_eq!(round.draw(), Err(RoundError::Ended));
+    }
+
+    #[test]
+    fn tsumo() {
+        let mut round = dealt(3, 0);
+        round.draw().unwrap();
+        assert_eq!(round.tsumo().err(), Some(RoundError::NotComplete));
+        let missing = (0..TILE_KINDS)
+            .map(Tile::from_index)
+            .find(|tile| !round.current().hand.contains(tile))
+            .unwrap();
+        assert_eq!(round.discard(missing), Err(RoundError::NotInHand(missing)));
+
+        // a closed hand won by tsumo has a yaku of its own.
+        let mut round = dealt(3, 0);
+        round.draw().unwrap();
+        round.players[0].hand =
+            tiles("M1 M2 M3 P4 P5 P6 S7 S8 S9 M5 M5 RD RD RD");
+        round.drawn = Some(tiles("RD")[0]);
+        let result = round.tsumo().unwrap().clone();
+        let RoundEnd::Win(wins) = &result.end else {
+            panic!("expected a win");
+        };
+        let win = &wins[0];
+        assert_eq!(win.winner, 0);
+        assert!(win.yakus.contains(&Yaku::FullyConcealedHand));
+        assert!(win.yakus.contains(&Yaku::Dragons(Dragon::Red)));
+        assert_eq!(result.payments.iter().sum::<i32>(), 0);
+        assert_eq!(
+            result.payments[0],
+            win.score.tsumo(true).total(true) as i32
+        );
+        assert_eq!(round.scores[0], 25000 + result.payments[0]);
+        assert_eq!(round.phase, Phase::Ended);
+
+        // each of the others pays a third of the honba.
+        let mut round = dealt(3, 0);
+        round.honba = 1;
+        round.draw().unwrap();
+        round.players[0].hand =
+            tiles("M1 M2 M3 P4 P5 P6 S7 S8 S9 M5 M5 RD RD RD");
+        round.drawn = Some(tiles("RD")[0]);
+        let honba = round.tsumo().unwrap();
+        assert_eq!(honba.payments[0], result.payments[0] + 300);
+        assert_eq!(honba.payments[1], result.payments[1] - 100);
+
+        // pinfu on top, and its 20 fu.
+        let mut round = dealt(3, 0);
+        round.draw().unwrap();
+        round.players[0].hand =
+            tiles("M2 M3 M4 P4 P5 P6 S6 S7 S8 M6 M7 P9 P9 M8");
+        round.drawn = Some(tiles("M8")[0]);
+        let result = round.tsumo().unwrap();
+        let RoundEnd::Win(wins) = &result.end else {
+            panic!("expected a win");
+        };
+        let win = &wins[0];
+        assert!(win.yakus.contains(&Yaku::Pinfu));
+        assert!(win.yakus.contains(&Yaku::FullyConcealedHand));
+        assert_eq!(win.score.fu, 20);
+        assert!(win.score.han >= 2);
+    }
+
+    #[test]
+    fn calls() {
+        let hands = [
+            "P3 P4 P5 P6 P7 M1 M9 S1 S9 EW SW WW NW",
+            "P5 P5 M2 M3 M4 S2 S3 S4 GD GD RD WD EW",
+            "P4 P6 M2 M3 M4 S2 S3 S4 GD GD RD WD EW",
+        ];
+        let p5 = tiles("P5")[0];
+        let start = |tile: &str| Claim::Call(Call::Chi(tiles(tile)[0]));
+
+        let mut round = discarding(Ruleset::riichi(), "P5", hands);
+        assert_eq!(round.discarded(), Some(p5));
+        assert_eq!(
+            round.available_claims(1),
+            vec![start("P3"), start("P4"), start("P5")]
+        );
+        assert_eq!(round.available_claims(2), vec![Claim::Call(Call::Pon)]);
+        // only the player after the discarder may chi.
+        assert_eq!(
+            round.claim(3, start("P4")),
+            Err(RoundError::CantClaim(start("P4")))
+        );
+
+        // pon goes before chi.
+        round.claim(1, start("P4")).unwrap();
+        round.claim(2, Claim::Call(Call::Pon)).unwrap();
+        round.resolve().unwrap();
+        assert_eq!(round.turn, 2);
+        assert_eq!(round.phase, Phase::Discard);
+        assert_eq!(round.players[2].dealt_in, vec![p5; 3]);
+        assert_eq!(round.players[2].hand.len(), 11);
+        assert_eq!(round.players[1].hand.len(), HAND_SIZE);
+        assert!(!round.players[0].discarded.contains(&p5));
+        assert_eq!(round.players[0].river(), vec![p5]);
+
+        let mut round = discarding(Ruleset::riichi(), "P5", hands);
+        round.claim(1, start("P4")).unwrap();
+        round.resolve().unwrap();
+        assert_eq!(round.turn, 1);
+        assert_eq!(round.players[1].dealt_in, tiles("P4 P5 P6"));
+        assert_eq!(round.draw(), Err(RoundError::WrongPhase(Phase::Discard)));
+
+        // nobody claims, the next player draws.
+        let mut round = discarding(Ruleset::riichi(), "P5", hands);
+        round.resolve().unwrap();
+        assert_eq!((round.turn, round.phase), (1, Phase::Draw));
+        assert_eq!(round.players[0].discarded.last(), Some(&p5));
+    }
+
+    #[test]
+    fn multiple_rons() {
+        let waiting = "M1 M2 M3 P4 P5 P6 S7 S8 S9 RD RD RD M5";
+        let hands = [waiting; 3];
+        let ruleset = |multiple_ron| Ruleset {
+            multiple_ron,
+            ..Ruleset::riichi()
+        };
+
+        let mut round =
+            discarding(ruleset(MultipleRon::TripleRonAbort), "M5", hands);
+        for position in 1..4 {
+            assert_eq!(round.available_claims(position), vec![Claim::Ron]);
+            round.claim(position, Claim::Ron).unwrap();
+        }
+        round.resolve().unwrap();
+        assert_eq!(
+            round.result.unwrap().end,
+            RoundEnd::AbortiveDraw(AbortiveDraw::TripleRon)
+        );
+
+        let mut round =
+            discarding(ruleset(MultipleRon::TripleRonAbort), "M5", hands);
+        round.claim(3, Claim::Ron).unwrap();
+        round.claim(2, Claim::Ron).unwrap();
+        round.resolve().unwrap();
+        let result = round.result.unwrap();
+        let RoundEnd::Win(wins) = &result.end else {
+            panic!("expected a win");
+        };
+        assert_eq!(
+            wins.iter().map(|win| win.winner).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(result.payments[1], 0);
+        assert_eq!(result.payments.iter().sum::<i32>(), 0);
+        assert_eq!(
+            result.payments[0],
+            -(wins[0].score.ron(false) as i32
+                + wins[1].score.ron(false) as i32)
+        );
+
+        // the head bump goes to the first winner after the discarder, along
+        // with the honba.
+        let mut round = discarding(ruleset(MultipleRon::HeadBump), "M5", hands);
+        round.honba = 2;
+        round.claim(3, Claim::Ron).unwrap();
+        round.claim(2, Claim::Ron).unwrap();
+        round.resolve().unwrap();
+        let result = round.result.unwrap();
+        let RoundEnd::Win(wins) = &result.end else {
+            panic!("expected a win");
+        };
+        assert_eq!(wins.len(), 1);
+        assert_eq!(wins[0].winner, 2);
+        assert_eq!(
+            result.payments[0],
+            -(wins[0].score.ron(false) as i32 + 2 * 300)
+        );
+        assert_eq!(result.payments[3], 0);
+
+        // a winning tile among the player's own discards is furiten.
+        let mut round =
+            discarding(ruleset(MultipleRon::DoubleRon), "M5", hands);
+        round.players[1].discarded.push(tiles("M5")[0]);
+        assert!(!round.available_claims(1).contains(&Claim::Ron));
+        assert_eq!(
+            round.claim(1, Claim::Ron),
+            Err(RoundError::CantClaim(Claim::Ron))
+        );
+    }
+
+    #[test]
+    fn kuikae() {
+        let hands = [
+            "P3 P4 P5 P6 P7 M1 M9 S1 S9 EW SW WW NW",
+            "M2 M3 M4 S2 S3 S4 GD GD RD WD EW NW NW",
+            "M2 M3 M4 S2 S3 S4 GD GD RD WD EW NW NW",
+        ];
+        let p5 = tiles("P5")[0];
+        let start = |tile: &str| Claim::Call(Call::Chi(tiles(tile)[0]));
+
+        // the called tile itself can't go right back out.
+        let mut round = discarding(Ruleset::riichi(), "P5", hands);
+        round.claim(1, start("P3")).unwrap();
+        round.resolve().unwrap();
+        assert_eq!(round.discard(p5), Err(RoundError::Kuikae(p5)));
+        round.discard(tiles("P6")[0]).unwrap();
+        assert!(round.kuikae.is_empty());
+
+        // nor can the other end of the sequence.
+        let mut round = discarding(Ruleset::riichi(), "P5", hands);
+        round.claim(1, start("P5")).unwrap();
+        round.resolve().unwrap();
+        assert_eq!(round.kuikae, tiles("P5 P8"));
+
+        let ruleset = Ruleset {
+            kuikae: Kuikae::Allowed,
+            ..Ruleset::riichi()
+        };
+        let mut round = discarding(ruleset, "P5", hands);
+        round.claim(1, start("P3")).unwrap();
+        round.resolve().unwrap();
+        round.discard(p5).unwrap();
+    }
+
+    #[test]
+    fn pao() {
+        let fed = |ruleset| {
+            let mut round = discarding(
+                ruleset,
+                "WD",
+                [
+                    "M1 M9 P1 P9 S1 S9 EW SW WW NW GD RD M5",
+                    "WD WD M2 M3 P1 P1 S9",
+                    "M1 M9 P1 P9 S1 S9 EW SW WW NW GD RD M5",
+                ],
+            );
+            round.players[2].dealt_in = tiles("GD GD GD RD RD RD");
+            round.claim(2, Claim::Call(Call::Pon)).unwrap();
+            round.resolve().unwrap();
+            round.discard(tiles("S9")[0]).unwrap();
+            round
+        };
+        let m4 = tiles("M4")[0];
+
+        // the player feeding the third dragon set pays the whole tsumo.
+        let mut round = fed(Ruleset::riichi());
+        assert_eq!(round.pao, vec![None, None, Some(0), None]);
+        pass_until(&mut round, 2);
+        round.draw().unwrap();
+        round.players[2].hand = tiles("M2 M3 M4 P1 P1");
+        round.drawn = Some(m4);
+        let result = round.tsumo().unwrap();
+        let RoundEnd::Win(wins) = &result.end else {
+            panic!("expected a win");
+        };
+        assert_eq!(wins[0].yakus, vec![Yaku::BigThreeDragons]);
+        assert_eq!(result.payments, vec![-32000, 0, 32000, 0]);
+
+        // and half of a ron.
+        let mut round = fed(Ruleset::riichi());
+        pass_until(&mut round, 3);
+        round.draw().unwrap();
+        round.players[3].hand.push(m4);
+        round.discard(m4).unwrap();
+        round.claim(2, Claim::Ron).unwrap();
+        round.resolve().unwrap();
+        assert_eq!(
+            round.result.unwrap().payments,
+            vec![-16000, 0, 32000, -16000]
+        );
+
+        let ruleset = Ruleset {
+            pao: false,
+            ..Ruleset::riichi()
+        };
+        assert_eq!(fed(ruleset).pao, vec![None; 4]);
+    }
+
+    #[test]
+    fn sanma() {
+        let sanma = |tsumo_loss| {
+            let ruleset = Ruleset {
+                tsumo_loss,
+                ..Ruleset::sanma()
+            };
+            let wall = Wall::shuffled(&ruleset, &mut Rng::new(4));
+            Round::new(ruleset, wall, Wind::East, 0, vec![35000; 3])
+        };
+        let north = tiles("NW")[0];
+
+        let mut round = sanma(true);
+        assert_eq!(round.players[2].seat, Wind::West);
+        assert_eq!(round.wall.remaining(), 108 - 14 - 3 * HAND_SIZE);
+        round.draw().unwrap();
+        round.players[0].hand[0] = north;
+        round.declare_nukidora().unwrap();
+        assert_eq!(round.players[0].bonus, vec![north]);
+        assert_eq!(round.players[0].hand.len(), HAND_SIZE + 1);
+        assert!(round.after_kan);
+
+        // the north counts as a dora on top of the indicated ones.
+        round.players[0].hand =
+            tiles("P1 P1 P1 P4 P5 P6 S7 S8 S9 P2 P2 RD RD RD");
+        round.drawn = Some(tiles("RD")[0]);
+        let doras = round.wall.doras(&round.ruleset);
+        let tiles_and_north = [round.players[0].hand.clone(), vec![north]];
+        let indicated = tiles_and_north
+            .concat()
+            .iter()
+            .filter(|tile| doras.contains(tile))
+            .count() as u8;
+        let result = round.tsumo().unwrap();
+        let RoundEnd::Win(wins) = &result.end else {
+            panic!("expected a win");
+        };
+        let han = wins[0].yakus.iter().map(|yaku| yaku.han(true)).sum::<u8>();
+        assert_eq!(wins[0].score.han, han + indicated + 1);
+
+        // with tsumo loss the missing player's share isn't paid, otherwise
+        // the others split it.
+        let share = wins[0].score.tsumo(true).non_dealer as i32;
+        assert_eq!(result.payments, vec![2 * share, -share, -share]);
+        let mut round = sanma(false);
+        round.draw().unwrap();
+        round.players[0].hand[0] = north;
+        round.declare_nukidora().unwrap();
+        round.players[0].hand =
+            tiles("P1 P1 P1 P4 P5 P6 S7 S8 S9 P2 P2 RD RD RD");
+        round.drawn = Some(tiles("RD")[0]);
+        let split = round.tsumo().unwrap();
+        let extra = (share / 2 + 99) / 100 * 100;
+        assert_eq!(split.payments[1], -(share + extra));
+
+        // no chi with three players.
+        let mut round = sanma(true);
+        round.draw().unwrap();
+        let p5 = tiles("P5")[0];
+        round.players[0].hand.push(p5);
+        round.players[1].hand = tiles("P3 P4 P5 P5 S1 S1 S2 S2 S3 S3 WD WD GD");
+        round.discard(p5).unwrap();
+        assert_eq!(round.available_claims(1), vec![Claim::Call(Call::Pon)]);
+        assert_eq!(
+            round.declare_nukidora(),
+            Err(RoundError::WrongPhase(Phase::Claims))
+        );
+
+        // a honba is 200, a 100 from each of the two payers.
+        let won = |honba| {
+            let mut round = sanma(true);
+            round.honba = honba;
+            round.draw().unwrap();
+            round.players[0].hand[0] = north;
+            round.declare_nukidora().unwrap();
+            round.players[0].hand =
+                tiles("P1 P1 P1 P4 P5 P6 S7 S8 S9 P2 P2 RD RD RD");
+            round.drawn = Some(tiles("RD")[0]);
+            round.tsumo().unwrap().payments.clone()
+        };
+        let (plain, repeated) = (won(0), won(2));
+        assert_eq!(repeated[0], plain[0] + 400);
+        assert_eq!(repeated[1], plain[1] - 200);
+        assert_eq!(repeated[2], plain[2] - 200);
+
+        // the noten penalty is 2000, 1000 a head.
+        let mut drained = sanma(true);
+        while drained.wall.remaining() > 0 {
+            let tile = drained.draw().unwrap().unwrap();
+            drained.discard(tile).unwrap();
+            drained.resolve().unwrap();
+        }
+        drained.calls.clear();
+        let tenpai = "M1 M1 M1 P4 P5 P6 S7 S8 S9 RD RD RD P5";
+        let noten = "M1 M9 P1 P4 P7 S1 S4 S7 EW SW WW NW GD";
+        let settle = |hands: [&str; 3]| {
+            let mut round = drained.clone();
+            for (player, hand) in round.players.iter_mut().zip(hands) {
+                player.hand = tiles(hand);
+                player.discarded = tiles("P5");
+            }
+            assert_eq!(round.draw(), Ok(None));
+            round.result.unwrap().payments
+        };
+        assert_eq!(settle([tenpai, noten, noten]), vec![2000, -1000, -1000]);
+        assert_eq!(settle([tenpai, noten, tenpai]), vec![1000, -2000, 1000]);
+    }
+
+    #[test]
+    fn events() {
+        let mut round = dealt(2, 0);
+        round.draw().unwrap();
+        // a late subscriber still gets the deal.
+        let events = round.subscribe();
+        let tile = round.current().hand[0];
+        round.discard(tile).unwrap();
+        let received: Vec<GameEvent> = events.try_iter().collect();
+        assert!(matches!(received[0], GameEvent::Deal { dealer: 0, .. }));
+        assert!(matches!(received[1], GameEvent::Draw { position: 0, .. }));
+        assert_eq!(received[2], GameEvent::Discard { position: 0, tile });
+        assert_eq!(received.len(), 3);
+
+        let hands = [
+            "P3 P4 P5 P6 P7 M1 M9 S1 S9 EW SW WW NW",
+            "P5 P5 M2 M3 M4 S2 S3 S4 GD GD RD WD EW",
+            "M1 M2 M3 P4 P5 P6 S7 S8 S9 RD RD RD P5",
+        ];
+        let p5 = tiles("P5")[0];
+        let mut round = discarding(Ruleset::riichi(), "P5", hands);
+        let events = round.subscribe();
+        round.claim(2, Claim::Call(Call::Pon)).unwrap();
+        round.resolve().unwrap();
+        assert_eq!(
+            events.try_iter().last(),
+            Some(GameEvent::Pon {
+                caller: 2,
+                discarder: 0,
+                tile: p5
+            })
+        );
+
+        let mut round = discarding(Ruleset::riichi(), "P5", hands);
+        round.claim(3, Claim::Ron).unwrap();
+        round.resolve().unwrap();
+        let received = round.events.clone();
+        let RoundEnd::Win(wins) = round.result.unwrap().end else {
+            panic!("expected a win");
+        };
+        assert_eq!(
+            received[received.len() - 2],
+            GameEvent::Win(wins[0].clone())
+        );
+        assert!(matches!(
+            received.last(),
+            Some(GameEvent::ScoreChange { payments, .. })
+                if payments[0] < 0 && payments[3] > 0
+        ));
+
+        // a dropped receiver unsubscribes.
+        let mut round = dealt(2, 0);
+        drop(round.subscribe());
+        round.draw().unwrap();
+        assert!(round.subscribers.is_empty());
+    }
+
+    /// the others throw what they draw until the player at `position` is
+    /// about to draw.
+    fn pass_until(round: &mut Round, position: usize) {
+        round.resolve().unwrap();
+        while round.turn != position {
+            let tile = round.draw().unwrap().unwrap();
+            round.discard(tile).unwrap();
+            round.resolve().unwrap();
+        }
+    }
+
+    #[test]
+    fn riichi() {
+        let mut round = dealt(5, 0);
+        round.draw().unwrap();
+        round.players[0].hand =
+            tiles("M1 M2 M3 P4 P5 P6 S7 S8 S9 M5 M6 EW EW NW");
+        round.drawn = Some(tiles("NW")[0]);
+        assert_eq!(
+            round.declare_riichi(tiles("M1")[0]),
+            Err(RoundError::CantRiichi("the hand isn't tenpai"))
+        );
+        round.declare_riichi(tiles("NW")[0]).unwrap();
+        assert!(round.players[0].riichi);
+        // the stick goes down once the discard isn't won on.
+        assert_eq!(round.scores[0], 25000);
+        pass_until(&mut round, 0);
+        assert_eq!(round.scores[0], 24000);
+        assert_eq!(round.riichi_sticks, 1);
+        // everything discarded since the declaration went by.
+        let rivers: usize = round.players[1..]
+            .iter()
+            .map(|player| player.river().len())
+            .sum();
+        assert_eq!(round.players[0].passed.len(), rivers);
+        assert!(round.players[1].passed.is_empty());
+        let declaration = round.declarations[0].unwrap();
+        assert!(declaration.double && declaration.ippatsu);
+
+        // a tile that doesn't win goes straight out.
+        let next = round.wall.clone().draw().unwrap();
+        assert!(!tiles("M4 M7").contains(&next));
+        round.draw().unwrap();
+        assert_eq!(round.phase, Phase::Claims);
+        assert_eq!(round.players[0].discarded.last(), Some(&next));
+        assert!(!round.declarations[0].unwrap().ippatsu);
+
+        // winning on the first draw after the declaration.
+        let mut round = dealt(5, 0);
+        round.draw().unwrap();
+        round.players[0].hand =
+            tiles("M1 M2 M3 P4 P5 P6 S7 S8 S9 M5 M6 EW EW NW");
+        round.declare_riichi(tiles("NW")[0]).unwrap();
+        pass_until(&mut round, 0);
+        let next = round.wall.clone().draw().unwrap();
+        round.players[0].hand = tiles("M1 M2 M3 P4 P5 P6 S7 S8 S9 RD RD RD");
+        round.players[0].hand.push(next);
+        round.draw().unwrap();
+        assert_eq!(round.phase, Phase::Discard);
+        assert_eq!(
+            round.discard(tiles("M1")[0]),
+            Err(RoundError::RiichiLocked)
+        );
+        let result = round.tsumo().unwrap().clone();
+        let RoundEnd::Win(wins) = &result.end else {
+            panic!("expected a win");
+        };
+        for yaku in [Yaku::DoubeRiichi, Yaku::Ippatsu, Yaku::FullyConcealedHand]
+        {
+            assert!(wins[0].yakus.contains(&yaku));
+        }
+        assert_eq!(
+            result.payments[0],
+            wins[0].score.tsumo(true).total(true) as i32 + 1000
+        );
+        assert_eq!(round.riichi_sticks, 0);
+
+        let mut round = dealt(5, 0);
+        round.draw().unwrap();
+        round.scores[0] = 500;
+        assert_eq!(
+            round.declare_riichi(round.current().hand[0]),
+            Err(RoundError::CantRiichi("there are no points for the stick"))
+        );
+    }
+
+    #[test]
+    fn kans() {
+        let m1 = tiles("M1")[0];
+        let mut round = dealt(5, 0);
+        round.draw().unwrap();
+        round.players[0].hand =
+            tiles("M1 M1 M1 P4 P5 P6 S7 S8 S9 RD RD RD M5 M1");
+        round.drawn = Some(m1);
+        round.players[0].riichi = true;
+        assert_eq!(round.available_kans(), vec![m1]);
+        round.declare_kan(m1).unwrap();
+        assert_eq!(round.players[0].kan, vec![m1]);
+        assert_eq!(round.players[0].melds(), 1);
+        assert_eq!(round.wall.dora_indicators().len(), 2);
+        assert!(round.after_kan);
+
+        // in riichi a kan that changes the waits isn't allowed.
+        let mut round = dealt(5, 0);
+        round.draw().unwrap();
+        round.players[0].hand =
+            tiles("M1 M1 M1 M2 M3 P4 P5 P6 S7 S8 S9 RD RD M1");
+        round.drawn = Some(m1);
+        assert_eq!(round.available_kans(), vec![m1]);
+        round.players[0].riichi = true;
+        assert!(round.available_kans().is_empty());
+        assert_eq!(round.declare_kan(m1), Err(RoundError::CantKan(m1)));
+
+        // the fourth tile of a called triplet.
+        let p5 = tiles("P5")[0];
+        let mut round = dealt(5, 0);
+        round.draw().unwrap();
+        round.players[0].hand = tiles("M1 M2 M3 P4 P6 S7 S8 S9 RD RD P5");
+        round.players[0].dealt_in = vec![p5; 3];
+        assert_eq!(round.available_kans(), vec![p5]);
+        round.declare_kan(p5).unwrap();
+        assert_eq!(round.players[0].melds(), 1);
+        assert_eq!(round.players[0].meld_tiles().len(), 4);
+        assert_eq!(round.players[0].hand.len(), 11);
+
+        // three chis sharing the 3 man aren't a triplet to add to.
+        let m3 = tiles("M3")[0];
+        let mut round = dealt(5, 0);
+        round.draw().unwrap();
+        round.players[0].hand = tiles("P4 P5 P6 RD M3");
+        round.players[0].dealt_in = tiles("M1 M2 M3 M2 M3 M4 M3 M4 M5");
+        assert!(round.available_kans().is_empty());
+        assert_eq!(round.declare_kan(m3), Err(RoundError::CantKan(m3)));
+
+        // a pon leaves the fourth tile in hand, but the kan has to wait for
+        // the caller's next draw.
+        let rd = tiles("RD")[0];
+        let hands = [
+            "P3 P4 P5 P6 P7 M1 M9 S1 S9 EW SW WW NW",
+            "RD RD RD M2 M3 M4 S2 S3 S4 P7 P8 GD GD",
+            "M1 M2 M3 P4 P5 P6 S7 S8 S9 WD WD NW NW",
+        ];
+        let mut round = discarding(Ruleset::riichi(), "RD", hands);
+        round.claim(2, Claim::Call(Call::Pon)).unwrap();
+        round.resolve().unwrap();
+        assert_eq!((round.turn, round.phase), (2, Phase::Discard));
+        assert!(round.available_kans().is_empty());
+        assert_eq!(round.declare_kan(rd), Err(RoundError::CantKan(rd)));
+        round.discard(tiles("P7")[0]).unwrap();
+        pass_until(&mut round, 2);
+        round.draw().unwrap();
+        assert!(round.available_kans().contains(&rd));
+    }
+
+    #[test]
+    fn exhaustive_draw() {
+        let mut drained = dealt(7, 0);
+        while drained.wall.remaining() > 0 {
+            let tile = drained.draw().unwrap().unwrap();
+            drained.discard(tile).unwrap();
+            drained.resolve().unwrap();
+        }
+        drained.calls.clear();
+        let tenpai = "M1 M2 M3 P4 P5 P6 S7 S8 S9 RD RD RD M5";
+        let noten = "M1 M4 M7 P1 P4 P7 S1 S4 S7 EW SW WW NW";
+
+        let settle = |hands: [&str; 4], rivers: [&str; 4]| {
+            let mut round = drained.clone();
+            for (player, (hand, river)) in
+                round.players.iter_mut().zip(hands.iter().zip(rivers))
+            {
+                player.hand = tiles(hand);
+                player.discarded = tiles(river);
+            }
+            assert_eq!(round.draw(), Ok(None));
+            round.result.unwrap()
+        };
+
+        let result =
+            settle([tenpai, noten, tenpai, noten], ["M5", "M5", "P5", "S5"]);
+        assert_eq!(
+            result.end,
+            RoundEnd::ExhaustiveDraw {
+                tenpai: vec![0, 2],
+                nagashi: vec![],
+            }
+        );
+        assert_eq!(result.payments, vec![1500, -1500, 1500, -1500]);
+        assert!(result.dealer_keeps);
+
+        let result =
+            settle([noten, tenpai, noten, noten], ["M5", "M5", "P5", "S5"]);
+        assert_eq!(result.payments, vec![-1000, 3000, -1000, -1000]);
+        assert!(!result.dealer_keeps);
+
+        // nobody pays for being noten next to a nagashi mangan.
+        let result = settle(
+            [tenpai, noten, tenpai, noten],
+            ["M5", "M5", "P5", "M1 P9 EW RD"],
+        );
+        let RoundEnd::ExhaustiveDraw { nagashi, .. } = &result.end else {
+            panic!("expected the wall to run out");
+        };
+        assert_eq!(nagashi.len(), 1);
+        assert_eq!(nagashi[0].winner, 3);
+        assert_eq!(nagashi[0].yakus, vec![Yaku::NagashiMangan]);
+        assert_eq!(result.payments, vec![-4000, -2000, -2000, 8000]);
+
+        // a called discard rules it out.
+        let mut round = drained.clone();
+        round.calls.push(Called {
+            caller: 0,
+            discarder: 3,
+            call: Call::Pon,
+            tile: tiles("EW")[0],
+        });
+        round.players[3].discarded = tiles("M1 P9 RD");
+        round.draw().unwrap();
+        let RoundEnd::ExhaustiveDraw { nagashi, .. } =
+            round.result.unwrap().end
+        else {
+            panic!("expected the wall to run out");
+        };
+        assert!(nagashi.is_empty());
+    }
+
+    #[test]
+    fn abortive_draws() {
+        // nine terminals on the first draw, offered rather than forced.
+        let mut round = dealt(2, 0);
+        round.draw().unwrap();
+        round.players[0].hand =
+            tiles("M1 M9 P1 P9 S1 S9 EW SW M2 M3 M4 P5 P6 WW");
+        assert!(round.can_declare_nine_terminals());
+        let mut eight = round.clone();
+        eight.players[0].hand.pop();
+        eight.players[0].hand.push(tiles("P7")[0]);
+        assert!(!eight.can_declare_nine_terminals());
+        assert_eq!(
+            eight.declare_nine_terminals(),
+            Err(RoundError::NotNineTerminals)
+        );
+        let mut disabled = round.clone();
+        disabled.ruleset.abortive_draws.nine_terminals = false;
+        assert!(!disabled.can_declare_nine_terminals());
+        round.declare_nine_terminals().unwrap();
+        let result = round.result.unwrap();
+        assert_eq!(
+            result.end,
+            RoundEnd::AbortiveDraw(AbortiveDraw::NineTerminals)
+        );
+        assert!(result.dealer_keeps);
+
+        // the same wind from everyone on the first go-around.
+        let north = tiles("NW")[0];
+        let mut round = dealt(2, 0);
+        for _ in 0..4 {
+            round.draw().unwrap();
+            round.players[round.turn].hand.push(north);
+            round.discard(north).unwrap();
+            round.resolve().unwrap();
+        }
+        assert_eq!(
+            round.result.unwrap().end,
+            RoundEnd::AbortiveDraw(AbortiveDraw::FourWinds)
+        );
+
+        // every player in riichi, with the last stick on the table.
+        let mut round = dealt(2, 0);
+        for discard in ["P1", "S1", "M9", "P9"] {
+            round.draw().unwrap();
+            let mut hand = tiles("M1 M2 M3 P4 P5 P6 S7 S8 S9 RD RD RD M5");
+            hand.extend(tiles(discard));
+            round.players[round.turn].hand = hand;
+            round.declare_riichi(tiles(discard)[0]).unwrap();
+            round.resolve().unwrap();
+        }
+        assert_eq!(
+            round.result.unwrap().end,
+            RoundEnd::AbortiveDraw(AbortiveDraw::FourRiichi)
+        );
+        assert_eq!(round.scores, vec![24000; 4]);
+        assert_eq!(round.riichi_sticks, 4);
+
+        // four kans, but only when more than one player made them.
+        let mut round = dealt(2, 0);
+        round.draw().unwrap();
+        round.players[1].kan = tiles("M1");
+        round.players[2].kan = tiles("P1 P9");
+        round.players[3].kan = tiles("S1");
+        assert!(round.available_kans().is_empty());
+        let mut alone = round.clone();
+        for player in alone.players.iter_mut() {
+            player.kan.clear();
+        }
+        alone.players[1].kan = tiles("M1 P1 P9 S1");
+        let tile = round.drawn.unwrap();
+        round.discard(tile).unwrap();
+        round.resolve().unwrap();
+        assert_eq!(
+            round.result.unwrap().end,
+            RoundEnd::AbortiveDraw(AbortiveDraw::FourKans)
+        );
+        alone.discard(tile).unwrap();
+        alone.resolve().unwrap();
+        assert_eq!(alone.phase, Phase::Draw);
+    }
+}
@@ -0,0 +1,302 @@
+use crate::{
+    call::Call,
+    decompose::Set,
+    tile::{Bonus, Dragon, Simple, Tile, Wind},
+};
+
+/// what happens when more than one player wins on the same discard.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MultipleRon {
+    /// only the first winner in turn order after the discarder wins.
+    HeadBump,
+    /// every player who called ron wins.
+    DoubleRon,
+    /// two players may win, three calling ron abort the round.
+    TripleRonAbort,
+}
+
+/// the abortive draws played with, besides `MultipleRon::TripleRonAbort`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct AbortiveDraws {
+    /// a player may abort with nine different terminals and honors on their
+    /// first draw.
+    pub nine_terminals: bool,
+    /// everyone discarding the same wind on the first go-around.
+    pub four_winds: bool,
+    pub four_riichi: bool,
+    /// four kans declared by more than one player.
+    pub four_kans: bool,
+}
+
+impl AbortiveDraws {
+    pub fn all() -> AbortiveDraws {
+        AbortiveDraws {
+            nine_terminals: true,
+            four_winds: true,
+            four_riichi: true,
+            four_kans: true,
+        }
+    }
+
+    pub fn none() -> AbortiveDraws {
+        AbortiveDraws {
+            nine_terminals: false,
+            four_winds: false,
+            four_riichi: false,
+            four_kans: false,
+        }
+    }
+}
+
+/// which discards count as swap-calling (kuikae) right after a chi or pon.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Kuikae {
+    Allowed,
+    /// only the called tile itself.
+    CalledTile,
+    /// the called tile and the tile at the other end of a chi's sequence,
+    /// like 6m after a chi of 3m with 4m 5m.
+    Strict,
+}
+
+impl Kuikae {
+    /// the tiles the caller may not discard after making `call` on `tile`.
+    pub fn forbidden(self, call: Call, tile: Tile) -> Vec<Tile> {
+        let start = match (self, call) {
+            (Kuikae::Allowed, _) | (_, Call::Kan) => return vec![],
+            (Kuikae::CalledTile, _) | (_, Call::Pon) => return vec![tile],
+            (Kuikae::Strict, Call::Chi(start)) => start,
+        };
+
+        let mut forbidden = vec![tile];
+        let sequence = Set::Sequence(start).tiles();
+        let (Some(index), Some(first)) = (tile.index(), start.index()) else {
+            return forbidden;
+        };
+        let number = first % 9;
+        if tile == sequence[0] && number < 6 {
+            forbidden.push(Tile::from_index(index + 3));
+        } else if tile == sequence[2] && number > 0 {
+            forbidden.push(Tile::from_index(index - 3));
+        }
+        forbidden
+    }
+}
+
+/// how many winds a game goes around for.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GameLength {
+    /// one round of east (tonpuusen).
+    EastOnly,
+    /// east and south rounds (hanchan).
+    EastSouth,
+}
+
+/// the table rules a game is played under.
+#[derive(Clone)]
+pub struct Ruleset {
+    /// three players play sanma: without 2m to 8m, with north tiles set
+    /// aside as nukidora and without chi.
+    pub players: usize,
+    /// with three players, whether the share the missing player would pay
+    /// on a tsumo is lost rather than split between the two others.
+    pub tsumo_loss: bool,
+    /// whether the eight flower and season tiles are in play.
+    pub bonus_tiles: bool,
+    pub multiple_ron: MultipleRon,
+    pub abortive_draws: AbortiveDraws,
+    pub kuikae: Kuikae,
+    /// whether a player who feeds the final set of big three dragons or
+    /// four big winds is liable for the yakuman.
+    pub pao: bool,
+    /// what the players who aren't tenpai pay those who are when the wall
+    /// runs out, split evenly on both sides.
+    pub noten_penalty: i32,
+    /// what each honba (repeat counter) adds to a win, split among the
+    /// payers of a tsumo.
+    pub honba_bonus: i32,
+    pub length: GameLength,
+    pub starting_points: i32,
+    /// the points someone has to reach for the game to end after its last
+    /// round, and that final scores are counted from.
+    pub target_points: i32,
+    /// points added to the final scores by placement, first to last.
+    pub uma: Vec<i32>,
+    /// whether the dealer may end the game by winning or being tenpai in
+    /// the last round while in first place.
+    pub agari_yame: bool,
+    /// whether the game ends when someone drops below zero.
+    pub busting: bool,
+}
+
+impl Ruleset {
+    pub fn riichi() -> Ruleset {
+        Ruleset {
+            players: 4,
+            tsumo_loss: false,
+            bonus_tiles: false,
+            multiple_ron: MultipleRon::TripleRonAbort,
+            abortive_draws: AbortiveDraws::all(),
+            kuikae: Kuikae::Strict,
+            pao: true,
+            noten_penalty: 3000,
+            honba_bonus: 300,
+            length: GameLength::EastSouth,
+            starting_points: 25000,
+            target_points: 30000,
+            uma: vec![20000, 10000, -10000, -20000],
+            agari_yame: true,
+            busting: true,
+        }
+    }
+
+    /// three-player riichi.
+    pub fn sanma() -> Ruleset {
+        Ruleset {
+            players: 3,
+            tsumo_loss: true,
+            abortive_draws: AbortiveDraws {
+                four_winds: false,
+                four_riichi: false,
+                ..AbortiveDraws::all()
+            },
+            // 1000 a head either way, as with four players.
+            noten_penalty: 2000,
+            honba_bonus: 200,
+            starting_points: 35000,
+            target_points: 40000,
+            uma: vec![15000, 0, -15000],
+            ..Ruleset::riichi()
+        }
+    }
+
+    /// riichi play with the flowers and seasons of the chinese-family
+    /// variants, and without the riichi-only kuikae, pao and abortive
+    /// draws. the scoring of hong kong, taiwanese and mcr play isn't
+    /// modelled, so they share this one table.
+    pub fn chinese() -> Ruleset {
+        Ruleset {
+            bonus_tiles: true,
+            multiple_ron: MultipleRon::HeadBump,
+            abortive_draws: AbortiveDraws::none(),
+            kuikae: Kuikae::Allowed,
+            pao: false,
+            ..Ruleset::riichi()
+        }
+    }
+
+    /// every tile of the set played with, four copies of each regular tile
+    /// plus one of each bonus tile when they're enabled.
+    pub fn tiles(&self) -> Vec<Tile> {
+        let mut kinds = vec![];
+        for number in 1..=9 {
+            if !self.is_sanma() || number == 1 || number == 9 {
+                kinds.push(Tile::Simple(Simple::Man(number)));
+            }
+            kinds.push(Tile::Simple(Simple::Pin(number)));
+            kinds.push(Tile::Simple(Simple::Sou(number)));
+        }
+        for wind in [Wind::East, Wind::South, Wind::West, Wind::North] {
+            kinds.push(Tile::Wind(wind));
+        }
+        for dragon in [Dragon::Green, Dragon::Red, Dragon::White] {
+            kinds.push(Tile::Dragon(dragon));
+        }
+
+        let mut tiles: Vec<Tile> =
+            kinds.iter().flat_map(|tile| [*tile; 4]).collect();
+
+        if self.bonus_tiles {
+            for number in 1..=4 {
+                tiles.push(Tile::Bonus(Bonus::Flower(number)));
+                tiles.push(Tile::Bonus(Bonus::Season(number)));
+            }
+        }
+
+        tiles
+    }
+
+    pub fn is_sanma(&self) -> bool {
+        self.players == 3
+    }
+
+    /// the dora an indicator points to, skipping the characters missing
+    /// from the set in sanma.
+    pub fn indicate_dora(&self, indicator: Tile) -> Tile {
+        match indicator {
+            Tile::Simple(Simple::Man(1)) if self.is_sanma() => {
+                Tile::Simple(Simple::Man(9))
+            }
+            indicator => indicator.indicate_dora(),
+        }
+    }
+
+    /// the oka, the difference between the target and starting points of
+    /// every player, which goes to whoever finishes first.
+    pub fn oka(&self, players: usize) -> i32 {
+        (self.target_points - self.starting_points) * players as i32
+    }
+}
+
+impl Default for Ruleset {
+    fn default() -> Self {
+        Ruleset::riichi()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        call::Call,
+        ruleset::{Kuikae, Ruleset},
+        tile::{Bonus, Tile},
+    };
+
+    fn tile(notation: &str) -> Tile {
+        notation.parse().unwrap()
+    }
+
+    #[test]
+    fn tiles() {
+        assert_eq!(Ruleset::riichi().tiles().len(), 136);
+
+        let tiles = Ruleset::chinese().tiles();
+        assert_eq!(tiles.len(), 144);
+        assert!(tiles.contains(&Tile::Bonus(Bonus::Season(4))));
+
+        for tile in tiles {
+            assert_eq!(tile.to_string().parse::<Tile>(), Ok(tile));
+        }
+    }
+
+    #[test]
+    fn sanma() {
+        let sanma = Ruleset::sanma();
+        let tiles = sanma.tiles();
+        assert_eq!(tiles.len(), 108);
+        assert!(!tiles.contains(&tile("M5")));
+        assert_eq!(sanma.indicate_dora(tile("M1")), tile("M9"));
+        assert_eq!(sanma.indicate_dora(tile("M9")), tile("M1"));
+        assert_eq!(sanma.indicate_dora(tile("P1")), tile("P2"));
+        assert_eq!(Ruleset::riichi().indicate_dora(tile("M1")), tile("M2"));
+    }
+
+    #[test]
+    fn kuikae() {
+        let (m3, m4, m6, m7) = (tile("M3"), tile("M4"), tile("M6"), tile("M7"));
+        assert_eq!(Kuikae::Strict.forbidden(Call::Chi(m4), m4), vec![m4, m7]);
+        assert_eq!(Kuikae::Strict.forbidden(Call::Chi(m4), m6), vec![m6, m3]);
+        assert_eq!(Kuikae::Strict.forbidden(Call::Chi(m3), m4), vec![m4]);
+        assert_eq!(Kuikae::CalledTile.forbidden(Call::Chi(m4), m4), vec![m4]);
+        assert_eq!(Kuikae::Strict.forbidden(Call::Pon, m4), vec![m4]);
+        assert!(Kuikae::Allowed.forbidden(Call::Pon, m4).is_empty());
+
+        // no other end past the suit.
+        let s7 = tile("S7");
+        assert_eq!(Kuikae::Strict.forbidden(Call::Chi(s7), s7), vec![s7]);
+        assert_eq!(
+            Kuikae::Strict.forbidden(Call::Chi(s7), tile("S9")),
+            vec![tile("S9"), tile("S6")]
+        );
+    }
+}
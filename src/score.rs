@@ -0,0 +1,369 @@
+use std::fmt::Display;
+
+use crate::{
+    decompose::{decompose, Set},
+    player::Player,
+    tile::{Tile, Wind},
+    wait::{set_shape, WaitShape},
+    yaku::Yaku,
+};
+
+/// the named point limits a hand's han can reach.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub enum Limit {
+    Mangan,
+    Haneman,
+    Baiman,
+    Sanbaiman,
+    /// 1 per 13 han, so a double yakuman is `Yakuman(2)`.
+    Yakuman(u8),
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Limit::Mangan => write!(f, "mangan"),
+            Limit::Haneman => write!(f, "haneman"),
+            Limit::Baiman => write!(f, "baiman"),
+            Limit::Sanbaiman => write!(f, "sanbaiman"),
+            Limit::Yakuman(1) => write!(f, "yakuman"),
+            Limit::Yakuman(count) => write!(f, "{}x yakuman", count),
+        }
+    }
+}
+
+/// what a tsumo costs each of the other players.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TsumoPayment {
+    /// paid by the dealer, 0 when the dealer is the one who won.
+    pub dealer: u32,
+    pub non_dealer: u32,
+}
+
+impl TsumoPayment {
+    /// everything the winner collects, `dealer` telling whether they are
+    /// the dealer.
+    pub fn total(&self, dealer: bool) -> u32 {
+        if dealer {
+            3 * self.non_dealer
+        } else {
+            self.dealer + 2 * self.non_dealer
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Score {
+    pub han: u8,
+    pub fu: u8,
+}
+
+impl Score {
+    pub fn new(han: u8, fu: u8) -> Score {
+        Score { han, fu }
+    }
+
+    pub fn limit(&self) -> Option<Limit> {
+        match self.han {
+            0..=4 if self.unlimited_base() < 2000 => None,
+            0..=5 => Some(Limit::Mangan),
+            6..=7 => Some(Limit::Haneman),
+            8..=10 => Some(Limit::Baiman),
+            11..=12 => Some(Limit::Sanbaiman),
+            han => Some(Limit::Yakuman(han / 13)),
+        }
+    }
+
+    /// the basic points every payment is a multiple of: fu times 2 to the
+    /// power of han plus 2, up to the limits.
+    pub fn base_points(&self) -> u32 {
+        match self.limit() {
+            None => self.unlimited_base(),
+            Some(Limit::Mangan) => 2000,
+            Some(Limit::Haneman) => 3000,
+            Some(Limit::Baiman) => 4000,
+            Some(Limit::Sanbaiman) => 6000,
+            Some(Limit::Yakuman(count)) => 8000 * count as u32,
+        }
+    }
+
+    /// what the discarder pays on a ron.
+    pub fn ron(&self, dealer: bool) -> u32 {
+        round_up(self.base_points() * if dealer { 6 } else { 4 })
+    }
+
+    pub fn tsumo(&self, dealer: bool) -> TsumoPayment {
+        let base = self.base_points();
+        if dealer {
+            TsumoPayment {
+                dealer: 0,
+                non_dealer: round_up(base * 2),
+            }
+        } else {
+            TsumoPayment {
+                dealer: round_up(base * 2),
+                non_dealer: round_up(base),
+            }
+        }
+    }
+
+    fn unlimited_base(&self) -> u32 {
+        (self.fu as u32) << (2 + self.han.min(13)).min(20)
+    }
+}
+
+impl Display for Score {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.limit() {
+            Some(limit) => write!(f, "{} han {}", self.han, limit),
+            None => write!(f, "{} han {} fu", self.han, self.fu),
+        }
+    }
+}
+
+fn round_up(points: u32) -> u32 {
+    points.div_ceil(100) * 100
+}
+
+/// one way of reading a complete hand around its winning tile.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Reading {
+    pub fu: u8,
+    /// a closed hand of sequences and a pair without value, won on a
+    /// two-sided wait.
+    pub pinfu: bool,
+}
+
+impl Reading {
+    /// the basic points of the reading over those of the other yakus and
+    /// dora, which multiply every reading alike below the limits.
+    fn points(&self) -> u32 {
+        (self.fu as u32) << (Yaku::Pinfu.han(true) * self.pinfu as u8)
+    }
+}
+
+/// the reading of the player's complete hand that scores the most,
+/// `player.hand` holding the winning tile. seven pairs are always 25 fu,
+/// and a hand that isn't complete has no reading.
+pub fn reading(
+    player: &Player,
+    winning_tile: Tile,
+    tsumo: bool,
+    prevalent_wind: Wind,
+) -> Option<Reading> {
+    let menzenchin = player.is_menzenchin();
+    let value_pair = |tile: Tile| -> u8 {
+        match tile {
+            Tile::Dragon(_) => 2,
+            Tile::Wind(wind) => {
+                2 * (wind == player.seat) as u8
+                    + 2 * (wind == prevalent_wind) as u8
+            }
+            _ => 0,
+        }
+    };
+
+    let mut called = 0;
+    for set in player.meld_sets() {
+        if let Set::Triplet(tile) = set {
+            let quad = player.kan.contains(&tile);
+            let concealed = quad && !player.dealt_in.contains(&tile);
+            called += triplet_fu(tile, concealed, quad);
+        }
+    }
+
+    let mut best: Option<Reading> = None;
+    for decomposition in decompose(&player.hand) {
+        // every place the winning tile can sit in, each its own wait.
+        let mut readings = vec![];
+        if decomposition.pair == winning_tile {
+            readings.push((None, WaitShape::Tanki));
+        }
+        for (index, set) in decomposition.sets.iter().enumerate() {
+            if set.contains(winning_tile) {
+                readings.push((Some(index), set_shape(*set, winning_tile)));
+            }
+        }
+
+        for (completed, wait) in readings {
+            let mut sets = called;
+            for (index, set) in decomposition.sets.iter().enumerate() {
+                if let Set::Triplet(tile) = set {
+                    // a triplet finished by a discard counts as open.
+                    let concealed = tsumo || completed != Some(index);
+                    sets += triplet_fu(*tile, concealed, false);
+                }
+            }
+            let pair = value_pair(decomposition.pair);
+
+            let pinfu = menzenchin
+                && sets == 0
+                && pair == 0
+                && wait == WaitShape::Ryanmen
+                && player.meld_sets().is_empty();
+            let fu = if pinfu {
+                if tsumo {
+                    20
+                } else {
+                    30
+                }
+            } else {
+                let mut fu = 20 + sets + pair + wait.fu();
+                if menzenchin && !tsumo {
+                    fu += 10;
+                }
+                if tsumo {
+                    fu += 2;
+                }
+                // an open hand without any fu is rounded to 30.
+                (fu.div_ceil(10) * 10).max(30)
+            };
+            let reading = Reading { fu, pinfu };
+            if best.is_none_or(|best| reading.points() > best.points()) {
+                best = Some(reading);
+            }
+        }
+    }
+
+    best.or_else(|| {
+        let pairs = Tile::counts(&player.hand)
+            .iter()
+            .all(|count| *count == 0 || *count == 2);
+        (menzenchin && player.hand.len() == 14 && pairs).then_some(Reading {
+            fu: 25,
+            pinfu: false,
+        })
+    })
+}
+
+/// the fu of the reading of the player's complete hand, see `reading`, and
+/// none for a hand that isn't complete.
+pub fn fu(
+    player: &Player,
+    winning_tile: Tile,
+    tsumo: bool,
+    prevalent_wind: Wind,
+) -> u8 {
+    reading(player, winning_tile, tsumo, prevalent_wind)
+        .map_or(0, |reading| reading.fu)
+}
+
+fn triplet_fu(tile: Tile, concealed: bool, quad: bool) -> u8 {
+    let mut fu = 2;
+    if tile.is_terminal_or_honor() {
+        fu *= 2;
+    }
+    if concealed {
+        fu *= 2;
+    }
+    if quad {
+        fu *= 4;
+    }
+    fu
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        player::{Player, WinContext},
+        score::{fu, reading, Limit, Reading, Score, TsumoPayment},
+        tile::{tiles, Wind},
+        yaku::Yaku,
+    };
+
+    #[test]
+    fn payments() {
+        assert_eq!(Score::new(1, 30).ron(false), 1000);
+        assert_eq!(Score::new(3, 40).ron(false), 5200);
+        assert_eq!(Score::new(4, 30).ron(true), 11600);
+        assert_eq!(Score::new(4, 40).limit(), Some(Limit::Mangan));
+        assert_eq!(Score::new(4, 40).ron(false), 8000);
+        assert_eq!(Score::new(7, 30).ron(true), 18000);
+        assert_eq!(Score::new(26, 0).limit(), Some(Limit::Yakuman(2)));
+        assert_eq!(
+            Score::new(2, 30).tsumo(false),
+            TsumoPayment {
+                dealer: 1000,
+                non_dealer: 500,
+            }
+        );
+        assert_eq!(Score::new(1, 30).tsumo(true).total(true), 1500);
+        assert_eq!(Score::new(5, 30).to_string(), "5 han mangan");
+    }
+
+    #[test]
+    fn fu_of_hands() {
+        let mut player = Player::new(
+            Wind::South,
+            tiles("M2 M3 M4 P3 P4 P5 S6 S7 S8 M6 M7 M8 P9 P9"),
+        );
+        let m8 = tiles("M8")[0];
+        let m7 = tiles("M7")[0];
+
+        // pinfu.
+        assert_eq!(fu(&player, m8, false, Wind::East), 30);
+        assert_eq!(fu(&player, m8, true, Wind::East), 20);
+        // a kanchan adds 2, rounded up to 40 on a ron.
+        assert_eq!(fu(&player, m7, false, Wind::East), 40);
+
+        // a closed triplet of terminals, and a shanpon on red dragons that
+        // only stays concealed on a tsumo.
+        player.hand = tiles("M1 M1 M1 P3 P4 P5 S6 S7 S8 RD RD P9 P9 RD");
+        let rd = tiles("RD")[0];
+        assert_eq!(fu(&player, rd, false, Wind::East), 50);
+        assert_eq!(fu(&player, rd, true, Wind::East), 40);
+
+        player.hand = tiles("M1 M1 P2 P2 S3 S3 EW EW SW SW GD GD RD RD");
+        assert_eq!(fu(&player, rd, false, Wind::East), 25);
+
+        // an open hand without fu.
+        player.hand = tiles("M2 M3 M4 P3 P4 P5 S6 S7 S8 P9 P9");
+        player.dealt_in = tiles("M6 M7 M8");
+        assert_eq!(fu(&player, tiles("M4")[0], false, Wind::East), 30);
+    }
+
+    #[test]
+    fn readings() {
+        // the 4 man completes the two-sided 23 man or pairs up a single 4
+        // man. the pinfu reading is worth more than the 40 fu of the other.
+        let player = Player::new(
+            Wind::South,
+            tiles("M2 M3 M4 M4 M4 P3 P4 P5 S6 S7 S8 P6 P7 P8"),
+        );
+        let m4 = tiles("M4")[0];
+        assert_eq!(
+            reading(&player, m4, false, Wind::East),
+            Some(Reading {
+                fu: 30,
+                pinfu: true
+            })
+        );
+        let context = WinContext {
+            winning_tile: Some(m4),
+            ..WinContext::new(Wind::East)
+        };
+        assert!(player.evaluate_win(&context).contains(&Yaku::Pinfu));
+
+        // on a tsumo it comes with the fully concealed hand.
+        let context = WinContext {
+            tsumo: true,
+            ..context
+        };
+        let yakus = player.evaluate_win(&context);
+        assert!(yakus.contains(&Yaku::Pinfu));
+        assert!(yakus.contains(&Yaku::FullyConcealedHand));
+        assert_eq!(fu(&player, m4, true, Wind::East), 20);
+
+        // a pair of the seat wind is worth fu, so no pinfu.
+        let player = Player::new(
+            Wind::South,
+            tiles("M2 M3 M4 S4 S5 S6 P3 P4 P5 S6 S7 S8 SW SW"),
+        );
+        let context = WinContext {
+            winning_tile: Some(m4),
+            ..WinContext::new(Wind::East)
+        };
+        assert!(!player.evaluate_win(&context).contains(&Yaku::Pinfu));
+        assert_eq!(reading(&player, tiles("P9")[0], false, Wind::East), None);
+    }
+}
@@ -0,0 +1,266 @@
+use crate::tile::{Tile, TILE_KINDS};
+
+/// how many tiles a hand is away from tenpai for each winning form. 0 is
+/// tenpai and -1 a complete hand. seven pairs and thirteen orphans only exist
+/// for fully concealed hands without melds.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Shanten {
+    pub standard: i8,
+    pub seven_pairs: Option<i8>,
+    pub thirteen_orphans: Option<i8>,
+}
+
+impl Shanten {
+    /// the breakdown for the concealed `tiles` of a hand with `melds` called
+    /// or declared sets.
+    pub fn of(tiles: &[Tile], melds: usize) -> Shanten {
+        let counts = Tile::counts(tiles);
+        Shanten {
+            standard: standard_shanten(&counts, melds),
+            seven_pairs: (melds == 0).then(|| seven_pairs_shanten(&counts)),
+            thirteen_orphans: (melds == 0)
+                .then(|| thirteen_orphans_shanten(&counts)),
+        }
+    }
+
+    pub fn min(&self) -> i8 {
+        self.standard
+            .min(self.seven_pairs.unwrap_or(i8::MAX))
+            .min(self.thirteen_orphans.unwrap_or(i8::MAX))
+    }
+}
+
+/// the shanten of the concealed `tiles` of a hand with `melds` sets, taking
+/// the best of the three winning forms.
+pub fn shanten(tiles: &[Tile], melds: usize) -> i8 {
+    shanten_of_counts(&Tile::counts(tiles), melds)
+}
+
+/// `shanten` for tiles already counted with `Tile::counts`.
+pub fn shanten_of_counts(counts: &[u8; TILE_KINDS], melds: usize) -> i8 {
+    let mut shanten = standard_shanten(counts, melds);
+    if melds == 0 {
+        shanten = shanten
+            .min(seven_pairs_shanten(counts))
+            .min(thirteen_orphans_shanten(counts));
+    }
+    shanten
+}
+
+pub fn seven_pairs_shanten(counts: &[u8; TILE_KINDS]) -> i8 {
+    let pairs = counts.iter().filter(|count| **count >= 2).count() as i8;
+    let kinds = counts.iter().filter(|count| **count >= 1).count() as i8;
+    // four of a kind is a single pair, the missing pairs have to come from
+    // tiles not held yet.
+    6 - pairs + (7 - kinds).max(0)
+}
+
+pub fn thirteen_orphans_shanten(counts: &[u8; TILE_KINDS]) -> i8 {
+    let orphans = (0..TILE_KINDS)
+        .filter(|index| Tile::from_index(*index).is_terminal_or_honor());
+    let mut kinds = 0;
+    let mut pair = 0;
+    for index in orphans {
+        if counts[index] >= 1 {
+            kinds += 1;
+        }
+        if counts[index] >= 2 {
+            pair = 1;
+        }
+    }
+    13 - kinds - pair
+}
+
+/// the best number of taatsu (incomplete sets) for each combination of
+/// having a pair and number of complete sets, -1 when the combination can't
+/// be made. every one of the four sets and the pair is a block, so anything
+/// past 4 taatsu is useless and capped.
+pub type Blocks = [[i8; 5]; 2];
+
+pub fn standard_shanten(counts: &[u8; TILE_KINDS], melds: usize) -> i8 {
+    let mut combined = [[-1; 5]; 2];
+    combined[0][0] = 0;
+    for (range, sequences) in [
+        (0..9, true),
+        (9..18, true),
+        (18..27, true),
+        (27..TILE_KINDS, false),
+    ] {
+        let mut suit = [0; 9];
+        suit[..range.len()].copy_from_slice(&counts[range]);
+        combined = combine(&combined, &suit_blocks(&suit, sequences));
+    }
+    shanten_of_blocks(&combined, melds)
+}
+
+/// the shanten from the blocks of a whole hand: 8 minus two per set, one per
+/// taatsu that still fits and one for the pair.
+pub fn shanten_of_blocks(blocks: &Blocks, melds: usize) -> i8 {
+    let mut shanten = 8;
+    for (pair, taatsu_by_sets) in blocks.iter().enumerate() {
+        for (sets, taatsu) in taatsu_by_sets.iter().enumerate() {
+            let sets = (sets + melds) as i8;
+            if *taatsu < 0 || sets > 4 {
+                continue;
+            }
+            let taatsu = (*taatsu).min(4 - sets);
+            shanten = shanten.min(8 - 2 * sets - taatsu - pair as i8);
+        }
+    }
+    shanten
+}
+
+/// merges the blocks of two disjoint groups of tiles.
+pub fn combine(first: &Blocks, second: &Blocks) -> Blocks {
+    let mut combined = [[-1; 5]; 2];
+    for first_pair in 0..2 {
+        for second_pair in 0..2 - first_pair {
+            for first_sets in 0..5 {
+                for second_sets in 0..5 - first_sets {
+                    let first_taatsu = first[first_pair][first_sets];
+                    let second_taatsu = second[second_pair][second_sets];
+                    if first_taatsu < 0 || second_taatsu < 0 {
+                        continue;
+                    }
+                    let entry = &mut combined[first_pair + second_pair]
+                        [first_sets + second_sets];
+                    *entry =
+                        (*entry).max((first_taatsu + second_taatsu).min(4));
+                }
+            }
+        }
+    }
+    combined
+}
+
+/// the blocks that can be made out of a single suit, or out of the honors
+/// when `sequences` is false.
+pub fn suit_blocks(counts: &[u8; 9], sequences: bool) -> Blocks {
+    let mut blocks = [[-1; 5]; 2];
+    let mut counts = *counts;
+    search(&mut counts, 0, sequences, 0, 0, 0, &mut blocks);
+    blocks
+}
+
+fn search(
+    counts: &mut [u8; 9],
+    mut index: usize,
+    sequences: bool,
+    sets: usize,
+    taatsu: i8,
+    pair: usize,
+    blocks: &mut Blocks,
+) {
+    while index < 9 && counts[index] == 0 {
+        index += 1;
+    }
+    if index == 9 {
+        let entry = &mut blocks[pair][sets.min(4)];
+        *entry = (*entry).max(taatsu.min(4));
+        return;
+    }
+
+    let sequence = sequences && index < 7;
+    let adjacent = sequences && index < 8;
+
+    if counts[index] >= 3 {
+        counts[index] -= 3;
+        search(counts, index, sequences, sets + 1, taatsu, pair, blocks);
+        counts[index] += 3;
+    }
+    if sequence && counts[index + 1] > 0 && counts[index + 2] > 0 {
+        counts[index] -= 1;
+        counts[index + 1] -= 1;
+        counts[index + 2] -= 1;
+        search(counts, index, sequences, sets + 1, taatsu, pair, blocks);
+        counts[index] += 1;
+        counts[index + 1] += 1;
+        counts[index + 2] += 1;
+    }
+    if counts[index] >= 2 {
+        counts[index] -= 2;
+        if pair == 0 {
+            search(counts, index, sequences, sets, taatsu, 1, blocks);
+        }
+        search(counts, index, sequences, sets, taatsu + 1, pair, blocks);
+        counts[index] += 2;
+    }
+    if adjacent && counts[index + 1] > 0 {
+        counts[index] -= 1;
+        counts[index + 1] -= 1;
+        search(counts, index, sequences, sets, taatsu + 1, pair, blocks);
+        counts[index] += 1;
+        counts[index + 1] += 1;
+    }
+    if sequence && counts[index + 2] > 0 {
+        counts[index] -= 1;
+        counts[index + 2] -= 1;
+        search(counts, index, sequences, sets, taatsu + 1, pair, blocks);
+        counts[index] += 1;
+        counts[index + 2] += 1;
+    }
+
+    // leaves the tile on its own.
+    counts[index] -= 1;
+    search(counts, index, sequences, sets, taatsu, pair, blocks);
+    counts[index] += 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        shanten::{shanten, Shanten},
+        tile::tiles,
+    };
+
+    #[test]
+    fn standard() {
+        let complete = tiles("M1 M2 M3 P4 P5 P6 S7 S8 S9 EW EW EW GD GD");
+        assert_eq!(shanten(&complete, 0), -1);
+
+        let tenpai = tiles("M1 M2 M3 P4 P5 P6 S7 S8 S9 EW EW GD GD");
+        assert_eq!(shanten(&tenpai, 0), 0);
+
+        let tanki = tiles("GD");
+        assert_eq!(shanten(&tanki, 4), 0);
+
+        let two_away = tiles("M1 M2 M3 P4 P5 S7 S8 S9 EW EW GD RD WD");
+        assert_eq!(shanten(&two_away, 0), 2);
+
+        // four sets and no pair candidate is still one away.
+        let no_pair = tiles("M1 M2 M3 M4 M5 M6 M7 M8 M9 P1 P2 P3 EW");
+        assert_eq!(shanten(&no_pair, 0), 0);
+        let no_pair = tiles("M1 M2 M3 M4 M5 M6 M7 M8 M9 P1 P2 EW SW");
+        assert_eq!(shanten(&no_pair, 0), 1);
+
+        // a triplet and a sequence sharing tiles.
+        let shared = tiles("M1 M1 M1 M2 M3 P4 P5 P6 S7 S8 S9 NW NW");
+        assert_eq!(shanten(&shared, 0), 0);
+
+        let worst = tiles("M1 M4 M7 P2 P5 P8 S3 S6 S9 EW SW WW NW");
+        assert_eq!(Shanten::of(&worst, 0).standard, 8);
+    }
+
+    #[test]
+    fn breakdown() {
+        let pairs = tiles("M1 M1 P2 P2 S3 S3 EW EW SW SW GD GD RD");
+        let breakdown = Shanten::of(&pairs, 0);
+        assert_eq!(breakdown.seven_pairs, Some(0));
+        assert_eq!(breakdown.min(), 0);
+        assert!(breakdown.standard > 0);
+
+        // four of a kind can't be two of the seven pairs, so it takes a new
+        // pair on top of pairing the single.
+        let quad = tiles("M1 M1 M1 M1 P2 P2 S3 S3 EW EW SW SW GD");
+        assert_eq!(Shanten::of(&quad, 0).seven_pairs, Some(2));
+
+        let orphans = tiles("M1 M9 P1 P9 S1 S9 EW SW WW NW GD RD WD");
+        let breakdown = Shanten::of(&orphans, 0);
+        assert_eq!(breakdown.thirteen_orphans, Some(0));
+        assert_eq!(shanten(&orphans, 0), 0);
+
+        let open = Shanten::of(&tiles("M1 M9 P1 P9 S1 S9 EW SW WW NW"), 1);
+        assert_eq!(open.thirteen_orphans, None);
+        assert_eq!(open.seven_pairs, None);
+    }
+}
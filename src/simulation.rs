@@ -0,0 +1,179 @@
+use crate::{
+    lookup,
+    rng::Rng,
+    tile::{Tile, TILE_KINDS},
+};
+
+/// how many games to play out per discard and for how long.
+#[derive(Debug, Clone, Copy)]
+pub struct Simulation {
+    pub samples: usize,
+    /// the draws the player has left before the wall runs out.
+    pub draws: usize,
+    pub seed: u64,
+}
+
+impl Simulation {
+    pub fn new(samples: usize, draws: usize, seed: u64) -> Simulation {
+        Simulation {
+            samples,
+            draws,
+            seed,
+        }
+    }
+}
+
+/// the estimated chances after a discard, as fractions of the samples.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Outcome {
+    pub discard: Tile,
+    pub tenpai: f32,
+    pub tsumo: f32,
+}
+
+/// plays every distinct discard from a hand that just drew (14 tiles minus
+/// melds) forward `simulation.samples` times. each sample draws from the
+/// shuffled `unseen` tiles and throws whatever keeps the shanten lowest,
+/// without calls or riichi, and counts whether the hand reached tenpai
+/// and whether it won by tsumo before the draws ran out. the most likely
+/// win comes first. every discard is played against the same shuffles, so
+/// their differences aren't just noise.
+pub fn simulate(
+    tiles: &[Tile],
+    melds: usize,
+    unseen: &[u8; TILE_KINDS],
+    simulation: &Simulation,
+) -> Vec<Outcome> {
+    let mut pool = vec![];
+    for (index, count) in unseen.iter().enumerate() {
+        pool.extend([Tile::from_index(index)].repeat(*count as usize));
+    }
+
+    let mut rng = Rng::new(simulation.seed);
+    let walls: Vec<Vec<usize>> = (0..simulation.samples)
+        .map(|_| {
+            rng.shuffle(&mut pool);
+            pool.iter()
+                .take(simulation.draws)
+                .filter_map(|tile| tile.index())
+                .collect()
+        })
+        .collect();
+
+    let counts = Tile::counts(tiles);
+    let mut outcomes = vec![];
+    for discard in 0..TILE_KINDS {
+        if counts[discard] == 0 {
+            continue;
+        }
+        let mut hand = counts;
+        hand[discard] -= 1;
+
+        let mut tenpai = 0;
+        let mut tsumo = 0;
+        for wall in &walls {
+            let (reached_tenpai, won) = play(hand, melds, wall);
+            tenpai += reached_tenpai as usize;
+            tsumo += won as usize;
+        }
+
+        let samples = simulation.samples.max(1) as f32;
+        outcomes.push(Outcome {
+            discard: Tile::from_index(discard),
+            tenpai: tenpai as f32 / samples,
+            tsumo: tsumo as f32 / samples,
+        });
+    }
+
+    outcomes.sort_by(|first, second| {
+        second
+            .tsumo
+            .total_cmp(&first.tsumo)
+            .then(second.tenpai.total_cmp(&first.tenpai))
+    });
+    outcomes
+}
+
+/// plays one sample, returning whether the hand reached tenpai and whether
+/// it won.
+fn play(
+    mut hand: [u8; TILE_KINDS],
+    melds: usize,
+    wall: &[usize],
+) -> (bool, bool) {
+    let mut tenpai = lookup::shanten(&hand, melds) == 0;
+    for drawn in wall {
+        hand[*drawn] += 1;
+        if lookup::is_complete(&hand, melds) {
+            return (true, true);
+        }
+
+        let mut best = (i8::MAX, usize::MAX, 0);
+        for discard in 0..TILE_KINDS {
+            if hand[discard] == 0 {
+                continue;
+            }
+            hand[discard] -= 1;
+            let candidate = (
+                lookup::shanten(&hand, melds),
+                neighbours(&hand, discard),
+                discard,
+            );
+            hand[discard] += 1;
+            best = best.min(candidate);
+        }
+        hand[best.2] -= 1;
+        tenpai |= best.0 == 0;
+    }
+    (tenpai, false)
+}
+
+/// how many tiles the hand holds close enough to `index` to build on it,
+/// the tie break between discards keeping the same shanten.
+fn neighbours(hand: &[u8; TILE_KINDS], index: usize) -> usize {
+    if index >= 27 {
+        return hand[index] as usize;
+    }
+    let suit = index / 9 * 9;
+    (index.saturating_sub(2).max(suit)..=(index + 2).min(suit + 8))
+        .map(|other| hand[other] as usize)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        efficiency::unseen,
+        simulation::{simulate, Simulation},
+        tile::tiles,
+    };
+
+    #[test]
+    fn outcomes() {
+        let hand = tiles("M1 M2 M3 M4 M5 M6 P2 P3 P4 S5 S6 S8 EW NW");
+        let simulation = Simulation::new(100, 12, 1);
+        let outcomes = simulate(&hand, 0, &unseen(&hand), &simulation);
+        assert_eq!(outcomes.len(), 14);
+
+        // the lone honors and the 8 sou keep the hand 1 shanten, breaking
+        // up a set doesn't.
+        let keeping = tiles("S8 EW NW");
+        assert!(outcomes[..3]
+            .iter()
+            .all(|outcome| keeping.contains(&outcome.discard)));
+        let best = &outcomes[0];
+        assert!(best.tenpai > 0.8);
+        assert!(best.tsumo > 0.2 && best.tsumo < best.tenpai);
+        assert!(outcomes
+            .iter()
+            .all(|outcome| outcome.tsumo <= outcome.tenpai));
+
+        // seeded, so the same simulation gives the same estimate.
+        assert_eq!(outcomes, simulate(&hand, 0, &unseen(&hand), &simulation));
+
+        // with no draws left there's nothing to win.
+        let simulation = Simulation::new(10, 0, 1);
+        let outcomes = simulate(&hand, 0, &unseen(&hand), &simulation);
+        assert_eq!(outcomes[0].tsumo, 0.0);
+    }
+}
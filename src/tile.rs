@@ -1,19 +1,19 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
-#[derive(PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
 pub enum Dragon {
     Green,
     Red,
     White,
 }
 
-#[derive(PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
 pub enum Simple {
     Man(u8),
     Pin(u8),
     Sou(u8),
 }
-#[derive(PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
 pub enum Wind {
     East,
     South,
@@ -21,17 +21,98 @@ pub enum Wind {
     North,
 }
 
-#[derive(PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+/// the eight bonus tiles of the chinese-family variants, numbered 1 to 4.
+/// they never form sets: they are set aside as soon as they are drawn and
+/// replaced from the wall.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub enum Bonus {
+    Flower(u8),
+    Season(u8),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
 pub enum Tile {
     Dragon(Dragon),
     Simple(Simple),
     Wind(Wind),
+    Bonus(Bonus),
 
     None,
 }
 
+impl Bonus {
+    /// flower and season 1 belong to east, 2 to south, 3 to west and 4 to
+    /// north.
+    pub fn matches_seat(self, seat: Wind) -> bool {
+        let number = match self {
+            Bonus::Flower(number) | Bonus::Season(number) => number,
+        };
+        number
+            == match seat {
+                Wind::East => 1,
+                Wind::South => 2,
+                Wind::West => 3,
+                Wind::North => 4,
+            }
+    }
+}
+
+/// number of distinct regular tiles, the size of the arrays indexed by
+/// `Tile::index`.
+pub const TILE_KINDS: usize = 34;
+
 impl Tile {
-    fn indicate_dora(self) -> Tile {
+    /// a dense index for the regular tiles: man 0-8, pin 9-17, sou 18-26,
+    /// winds 27-30 and dragons 31-33. bonus tiles and `None` have none.
+    pub fn index(self) -> Option<usize> {
+        match self {
+            Tile::Simple(Simple::Man(number)) => Some(number as usize - 1),
+            Tile::Simple(Simple::Pin(number)) => Some(number as usize + 8),
+            Tile::Simple(Simple::Sou(number)) => Some(number as usize + 17),
+            Tile::Wind(wind) => Some(27 + wind as usize),
+            Tile::Dragon(dragon) => Some(31 + dragon as usize),
+            Tile::Bonus(_) | Tile::None => None,
+        }
+    }
+
+    /// the inverse of `index`.
+    pub fn from_index(index: usize) -> Tile {
+        const WINDS: [Wind; 4] =
+            [Wind::East, Wind::South, Wind::West, Wind::North];
+        const DRAGONS: [Dragon; 3] =
+            [Dragon::Green, Dragon::Red, Dragon::White];
+        let number = (index % 9) as u8 + 1;
+        match index {
+            0..=8 => Tile::Simple(Simple::Man(number)),
+            9..=17 => Tile::Simple(Simple::Pin(number)),
+            18..=26 => Tile::Simple(Simple::Sou(number)),
+            27..=30 => Tile::Wind(WINDS[index - 27]),
+            31..=33 => Tile::Dragon(DRAGONS[index - 31]),
+            _ => Tile::None,
+        }
+    }
+
+    /// how many of each regular tile `tiles` holds, indexed by `index`.
+    pub fn counts(tiles: &[Tile]) -> [u8; TILE_KINDS] {
+        let mut counts = [0; TILE_KINDS];
+        for index in tiles.iter().filter_map(|tile| tile.index()) {
+            counts[index] += 1;
+        }
+        counts
+    }
+
+    pub fn is_terminal_or_honor(self) -> bool {
+        matches!(
+            self,
+            Tile::Simple(Simple::Man(1 | 9))
+                | Tile::Simple(Simple::Pin(1 | 9))
+                | Tile::Simple(Simple::Sou(1 | 9))
+                | Tile::Wind(_)
+                | Tile::Dragon(_)
+        )
+    }
+
+    pub fn indicate_dora(self) -> Tile {
         match self {
             Tile::Dragon(Dragon::Green) => Tile::Dragon(Dragon::Red),
             Tile::Dragon(Dragon::Red) => Tile::Dragon(Dragon::White),
@@ -60,7 +141,7 @@ impl Tile {
             Tile::Wind(Wind::West) => Tile::Wind(Wind::North),
             Tile::Wind(Wind::North) => Tile::Wind(Wind::East),
 
-            Tile::None => Tile::None,
+            Tile::Bonus(_) | Tile::None => Tile::None,
         }
     }
 }
@@ -81,7 +162,84 @@ impl Display for Tile {
             Tile::Wind(Wind::West) => write!(f, "WW"),
             Tile::Wind(Wind::North) => write!(f, "NW"),
 
+            Tile::Bonus(Bonus::Flower(number)) => write!(f, "FL{}", number),
+            Tile::Bonus(Bonus::Season(number)) => write!(f, "SE{}", number),
+
             Tile::None => write!(f, "!!"),
         }
     }
 }
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseTileError(pub String);
+
+impl Display for ParseTileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid tile: {}", self.0)
+    }
+}
+
+impl FromStr for Tile {
+    type Err = ParseTileError;
+
+    /// parses the notation used by `Display`, e.g. `M1`, `GD`, `EW` or `FL1`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tile = match s {
+            "GD" => Tile::Dragon(Dragon::Green),
+            "RD" => Tile::Dragon(Dragon::Red),
+            "WD" => Tile::Dragon(Dragon::White),
+
+            "EW" => Tile::Wind(Wind::East),
+            "SW" => Tile::Wind(Wind::South),
+            "WW" => Tile::Wind(Wind::West),
+            "NW" => Tile::Wind(Wind::North),
+
+            "FL1" | "FL2" | "FL3" | "FL4" => {
+                Tile::Bonus(Bonus::Flower(s.as_bytes()[2] - b'0'))
+            }
+            "SE1" | "SE2" | "SE3" | "SE4" => {
+                Tile::Bonus(Bonus::Season(s.as_bytes()[2] - b'0'))
+            }
+
+            _ => {
+                let mut chars = s.chars();
+                let parts = match (chars.next(), chars.next(), chars.next()) {
+                    (Some(suit), Some(digit @ '1'..='9'), None) => {
+                        (suit, digit as u8 - b'0')
+                    }
+                    _ => return Err(ParseTileError(s.to_string())),
+                };
+                match parts {
+                    ('M', number) => Tile::Simple(Simple::Man(number)),
+                    ('P', number) => Tile::Simple(Simple::Pin(number)),
+                    ('S', number) => Tile::Simple(Simple::Sou(number)),
+                    _ => return Err(ParseTileError(s.to_string())),
+                }
+            }
+        };
+        Ok(tile)
+    }
+}
+
+impl FromStr for Wind {
+    type Err = ParseTileError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "East" => Ok(Wind::East),
+            "South" => Ok(Wind::South),
+            "West" => Ok(Wind::West),
+            "North" => Ok(Wind::North),
+            _ => Err(ParseTileError(s.to_string())),
+        }
+    }
+}
+
+/// the tiles of a notation like `M1 M2 M3 RD`, for tests.
+#[cfg(test)]
+pub(crate) fn tiles(notation: &str) -> Vec<Tile> {
+    notation
+        .split_whitespace()
+        .map(|tile| tile.parse().unwrap())
+        .collect()
+}
@@ -0,0 +1,289 @@
+use std::fmt::Display;
+
+use crate::{
+    decompose::{decompose, Set},
+    lookup,
+    shanten::{seven_pairs_shanten, thirteen_orphans_shanten},
+    tile::{Tile, TILE_KINDS},
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub enum WaitShape {
+    /// two-sided sequence wait, e.g. 34 waiting on 2 or 5.
+    Ryanmen,
+    /// closed wait in the middle of a sequence, e.g. 35 waiting on 4.
+    Kanchan,
+    /// edge wait, 12 waiting on 3 or 89 waiting on 7.
+    Penchan,
+    /// two pairs waiting to turn one of them into a triplet.
+    Shanpon,
+    /// single tile waiting for its pair.
+    Tanki,
+    /// four in a row waiting on either end for the pair, e.g. 3456 on 3 or
+    /// 6.
+    Nobetan,
+    /// three or more winning tiles of the same suit, or the thirteen-sided
+    /// thirteen orphans.
+    MultiSided,
+}
+
+impl WaitShape {
+    /// fu awarded for winning on this shape.
+    pub fn fu(self) -> u8 {
+        match self {
+            WaitShape::Kanchan
+            | WaitShape::Penchan
+            | WaitShape::Tanki
+            | WaitShape::Nobetan => 2,
+            WaitShape::Ryanmen | WaitShape::Shanpon | WaitShape::MultiSided => {
+                0
+            }
+        }
+    }
+}
+
+impl Display for WaitShape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WaitShape::Ryanmen => write!(f, "ryanmen"),
+            WaitShape::Kanchan => write!(f, "kanchan"),
+            WaitShape::Penchan => write!(f, "penchan"),
+            WaitShape::Shanpon => write!(f, "shanpon"),
+            WaitShape::Tanki => write!(f, "tanki"),
+            WaitShape::Nobetan => write!(f, "nobetan"),
+            WaitShape::MultiSided => write!(f, "multi-sided"),
+        }
+    }
+}
+
+/// a tile that completes a tenpai hand.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Wait {
+    pub tile: Tile,
+    /// every shape the tile completes, one per way of reading the hand.
+    pub shapes: Vec<WaitShape>,
+    /// all four copies are already in the player's own hand or melds.
+    pub impossible: bool,
+}
+
+/// the winning tiles of the concealed `tiles` of a tenpai hand with `melds`
+/// sets. `impossible` only accounts for copies in `tiles`, see
+/// `Player::waits` for one that also looks at melds.
+pub fn waits(tiles: &[Tile], melds: usize) -> Vec<Wait> {
+    let mut waits = vec![];
+    let mut counts = Tile::counts(tiles);
+    // one of each terminal and honor, so any of them makes the pair.
+    let thirteen_sided = melds == 0
+        && thirteen_orphans_shanten(&counts) == 0
+        && counts.iter().all(|count| *count <= 1);
+
+    for index in 0..TILE_KINDS {
+        let tile = Tile::from_index(index);
+        let impossible = counts[index] >= 4;
+
+        counts[index] += 1;
+        // only the tiles that complete the hand are worth reading it for.
+        if !lookup::is_complete(&counts, melds) {
+            counts[index] -= 1;
+            continue;
+        }
+        let mut shapes = vec![];
+        let mut hand = tiles.to_vec();
+        hand.push(tile);
+
+        for decomposition in decompose(&hand) {
+            if decomposition.pair == tile {
+                shapes.push(WaitShape::Tanki);
+            }
+            for set in
+                decomposition.sets.iter().filter(|set| set.contains(tile))
+            {
+                shapes.push(set_shape(*set, tile));
+            }
+        }
+
+        if melds == 0 {
+            if seven_pairs_shanten(&counts) == -1 {
+                shapes.push(WaitShape::Tanki);
+            }
+            if thirteen_orphans_shanten(&counts) == -1 {
+                shapes.push(if thirteen_sided {
+                    WaitShape::MultiSided
+                } else {
+                    WaitShape::Tanki
+                });
+            }
+        }
+        counts[index] -= 1;
+
+        if !shapes.is_empty() {
+            shapes.sort();
+            shapes.dedup();
+            waits.push(Wait {
+                tile,
+                shapes,
+                impossible,
+            });
+        }
+    }
+
+    let winning: Vec<usize> =
+        waits.iter().filter_map(|wait| wait.tile.index()).collect();
+
+    for wait in waits.iter_mut() {
+        let index = wait.tile.index().unwrap_or_default();
+        if index >= 27 {
+            continue;
+        }
+        let suit = index / 9;
+
+        // 3456 reads as a tanki on 3 with 456 or on 6 with 345.
+        let nobetan = [index.checked_sub(3), Some(index + 3)]
+            .into_iter()
+            .flatten()
+            .filter(|other| other / 9 == suit && winning.contains(other))
+            .any(|other| {
+                let (low, high) = (index.min(other), index.max(other));
+                (low + 1..high).all(|between| counts[between] > 0)
+            });
+        if nobetan && wait.shapes.contains(&WaitShape::Tanki) {
+            wait.shapes.retain(|shape| *shape != WaitShape::Tanki);
+            wait.shapes.push(WaitShape::Nobetan);
+        }
+
+        if winning.iter().filter(|other| **other / 9 == suit).count() >= 3 {
+            wait.shapes.push(WaitShape::MultiSided);
+        }
+        wait.shapes.sort();
+        wait.shapes.dedup();
+    }
+
+    waits
+}
+
+/// the wait `tile` completed `set` from.
+pub fn set_shape(set: Set, tile: Tile) -> WaitShape {
+    match set {
+        Set::Triplet(_) => WaitShape::Shanpon,
+        Set::Sequence(start) => {
+            let start = start.index().unwrap_or_default();
+            let index = tile.index().unwrap_or_default();
+            match (index - start, start % 9) {
+                (1, _) => WaitShape::Kanchan,
+                // 12 waiting on 3 and 89 waiting on 7.
+                (2, 0) | (0, 6) => WaitShape::Penchan,
+                _ => WaitShape::Ryanmen,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        tile::tiles,
+        wait::{waits, WaitShape},
+    };
+
+    fn shapes(notation: &str, melds: usize) -> Vec<(String, Vec<WaitShape>)> {
+        waits(&tiles(notation), melds)
+            .into_iter()
+            .map(|wait| (wait.tile.to_string(), wait.shapes))
+            .collect()
+    }
+
+    #[test]
+    fn shapes_of_single_waits() {
+        use WaitShape::*;
+
+        assert_eq!(
+            shapes("M1 M2 M3 P4 P5 P6 S7 S8 S9 EW EW EW P2", 0),
+            vec![("P2".to_string(), vec![Tanki])]
+        );
+        assert_eq!(
+            shapes("M1 M2 M3 P4 P5 P6 S7 S8 S9 EW EW P1 P3", 0),
+            vec![("P2".to_string(), vec![Kanchan])]
+        );
+        assert_eq!(
+            shapes("M1 M2 M3 P4 P5 P6 S7 S8 S9 EW EW P1 P2", 0),
+            vec![("P3".to_string(), vec![Penchan])]
+        );
+        assert_eq!(
+            shapes("M1 M2 M3 P4 P5 P6 EW EW S8 S9", 1),
+            vec![("S7".to_string(), vec![Penchan])]
+        );
+        assert_eq!(
+            shapes("M1 M2 M3 M4 M5 M6 S7 S8 S9 EW EW P3 P4", 0),
+            vec![
+                ("P2".to_string(), vec![Ryanmen]),
+                ("P5".to_string(), vec![Ryanmen]),
+            ]
+        );
+        assert_eq!(
+            shapes("M1 M2 M3 P4 P5 P6 S7 S8 S9 EW EW GD GD", 0),
+            vec![
+                ("EW".to_string(), vec![Shanpon]),
+                ("GD".to_string(), vec![Shanpon]),
+            ]
+        );
+        assert_eq!(
+            shapes("M1 M2 M3 P7 P8 P9 S7 S8 S9 P2 P3 P4 P5", 0),
+            vec![
+                ("P2".to_string(), vec![Nobetan]),
+                ("P5".to_string(), vec![Nobetan]),
+            ]
+        );
+    }
+
+    #[test]
+    fn multiple_readings() {
+        use WaitShape::*;
+
+        // 23456 waits on 1, 4 and 7.
+        let waits = shapes("M2 M3 M4 M5 M6 P4 P5 P6 S7 S8 S9 EW EW", 0);
+        assert_eq!(
+            waits,
+            vec![
+                ("M1".to_string(), vec![Ryanmen, MultiSided]),
+                ("M4".to_string(), vec![Ryanmen, MultiSided]),
+                ("M7".to_string(), vec![Ryanmen, MultiSided]),
+            ]
+        );
+
+        // 34 next to 456 also reads as 345 with a kanchan on 5.
+        let waits = shapes("M1 M2 M3 P4 P5 P6 S7 S8 S9 EW EW P3 P4", 0);
+        assert_eq!(waits[1], ("P5".to_string(), vec![Ryanmen, Kanchan]));
+
+        // 1113 reads as a kanchan on 2 with the pair 11, or a tanki on 3.
+        let waits = shapes("M1 M1 M1 M3 P4 P5 P6 S7 S8 S9 EW EW EW", 0);
+        assert_eq!(
+            waits,
+            vec![
+                ("M2".to_string(), vec![Kanchan]),
+                ("M3".to_string(), vec![Tanki]),
+            ]
+        );
+
+        let waits = shapes("M1 M1 P2 P2 S3 S3 EW EW SW SW GD GD RD", 0);
+        assert_eq!(waits, vec![("RD".to_string(), vec![Tanki])]);
+
+        let waits = shapes("M1 M9 P1 P9 S1 S9 EW SW WW NW GD RD WD", 0);
+        assert_eq!(waits.len(), 13);
+        assert!(waits.iter().all(|(_, shapes)| shapes == &vec![MultiSided]));
+
+        let waits = shapes("M1 M1 P1 P9 S1 S9 EW SW WW NW GD RD WD", 0);
+        assert_eq!(waits, vec![("M9".to_string(), vec![Tanki])]);
+    }
+
+    #[test]
+    fn impossible_waits() {
+        let waits = waits(&tiles("P1 P1 P1 P2"), 3);
+        assert_eq!(waits.len(), 2);
+        assert!(waits.iter().all(|wait| !wait.impossible));
+
+        // a concealed quad can only wait on a fifth copy.
+        let waits = super::waits(&tiles("P1 P1 P1 P1"), 3);
+        assert_eq!(waits.len(), 1);
+        assert!(waits[0].impossible);
+    }
+}
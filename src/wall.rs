@@ -0,0 +1,372 @@
+use std::{collections::VecDeque, fmt::Display, str::FromStr};
+
+use crate::{
+    rng::{entropy, Rng},
+    ruleset::Ruleset,
+    sha256::sha256_hex,
+    tile::Tile,
+};
+
+/// tiles set aside at the end of the wall for replacement draws and dora
+/// indicators.
+pub const DEAD_WALL_SIZE: usize = 14;
+
+/// replacement tiles sitting in front of the first dora indicator.
+const RINSHAN_TILES: usize = 4;
+
+/// the most dora indicators a round can have, one plus one per kan.
+const MAX_INDICATORS: usize = 5;
+
+/// the sides a wall of `tiles` is stacked on: four, or one per player for
+/// the 108 tiles of sanma, which don't fill four equal sides.
+fn sides(tiles: usize) -> Option<usize> {
+    [4, 3]
+        .into_iter()
+        .find(|sides| tiles > 0 && tiles.is_multiple_of(2 * sides))
+}
+
+/// a fresh secret for `Wall::hash`, 128 bits in hex.
+pub fn salt() -> String {
+    format!("{:016x}{:016x}", entropy(), entropy())
+}
+
+#[derive(Clone)]
+pub struct Wall {
+    /// the stacking order and dice the wall was built with, kept around so
+    /// it can be exported and verified after the round.
+    tiles: Vec<Tile>,
+    dice: (u8, u8),
+    /// the live wall in drawing order. the back is the last tile (haitei).
+    live: VecDeque<Tile>,
+    /// the dead wall starting from the end next to the break. the first four
+    /// tiles are the replacement tiles, followed by the indicator stacks.
+    /// tiles moved over from the live wall are appended after those.
+    dead: Vec<Tile>,
+    replacements: usize,
+    kan_doras: usize,
+}
+
+impl Wall {
+    /// builds a wall out of `tiles` stacked two high around the table and
+    /// breaks it with the sum of `dice`.
+    ///
+    /// the sides below are those of four players. three sides work the
+    /// same way, with the dice counting around three players.
+    ///
+    /// tiles `2k` and `2k + 1` are the top and bottom of stack `k`. stacks
+    /// are numbered clockwise, which is the drawing direction, starting at
+    /// the right end of the dealer's side. the dice pick a side counting
+    /// counter-clockwise from the dealer and the wall is broken that many
+    /// stacks from its right end: the live wall starts after the break and
+    /// the seven stacks before it form the dead wall.
+    pub fn new(tiles: Vec<Tile>, dice: (u8, u8)) -> Wall {
+        let sides = sides(tiles.len()).expect("tiles must fill equal sides");
+        assert!((1..=6).contains(&dice.0) && (1..=6).contains(&dice.1));
+
+        let stacks = tiles.len() / 2;
+        let stacks_per_side = stacks / sides;
+        let total = (dice.0 + dice.1) as usize;
+
+        // sides in clockwise order are the dealer's, the one on their left,
+        // across and on their right, while the dice count the other way.
+        let side = (sides - (total - 1) % sides) % sides;
+        let break_stack = side * stacks_per_side + total;
+
+        let stack = |index: usize| {
+            let index = index % stacks;
+            [tiles[2 * index], tiles[2 * index + 1]]
+        };
+
+        let dead_stacks = DEAD_WALL_SIZE / 2;
+        let live = (break_stack..break_stack + stacks - dead_stacks)
+            .flat_map(stack)
+            .collect();
+        let dead = (1..=dead_stacks)
+            .flat_map(|offset| stack(break_stack + stacks - offset))
+            .collect();
+
+        Wall {
+            tiles,
+            dice,
+            live,
+            dead,
+            replacements: 0,
+            kan_doras: 0,
+        }
+    }
+
+    /// shuffles the ruleset's tiles and rolls the dice with `rng`. the same
+    /// seed always builds the same wall.
+    pub fn shuffled(ruleset: &Ruleset, rng: &mut Rng) -> Wall {
+        let mut tiles = ruleset.tiles();
+        rng.shuffle(&mut tiles);
+        let dice = (rng.roll_die(), rng.roll_die());
+        Wall::new(tiles, dice)
+    }
+
+    pub fn dice(&self) -> (u8, u8) {
+        self.dice
+    }
+
+    /// SHA-256 of `salt`, a space and the exported wall. publishing it
+    /// before the round and the wall and salt afterwards proves the deal
+    /// wasn't changed in between. the salt has to stay secret until then,
+    /// or the hash could be matched against the walls of guessed seeds.
+    pub fn hash(&self, salt: &str) -> String {
+        sha256_hex(format!("{} {}", salt, self).as_bytes())
+    }
+
+    /// draws the next tile from the live wall.
+    pub fn draw(&mut self) -> Option<Tile> {
+        self.live.pop_front()
+    }
+
+    /// draws a replacement tile from the dead wall, after a kan or for a
+    /// bonus tile, and replenishes the dead wall with the last tile of the
+    /// live wall. there's no replacement once the live wall is exhausted.
+    pub fn draw_replacement(&mut self) -> Option<Tile> {
+        let refill = self.live.pop_back()?;
+        let index = if self.replacements < RINSHAN_TILES {
+            self.replacements
+        } else {
+            DEAD_WALL_SIZE + self.replacements - RINSHAN_TILES
+        };
+        self.dead.push(refill);
+        self.replacements += 1;
+        Some(self.dead[index])
+    }
+
+    /// turns over the next kan dora indicator. returns false when all of them
+    /// are already showing.
+    pub fn reveal_kan_dora(&mut self) -> bool {
+        if self.kan_doras + 1 >= MAX_INDICATORS {
+            return false;
+        }
+        self.kan_doras += 1;
+        true
+    }
+
+    /// position in the dead wall of the `n`th dora indicator.
+    pub fn dora_indicator_position(n: usize) -> usize {
+        RINSHAN_TILES + 2 * n
+    }
+
+    /// position in the dead wall of the `n`th ura dora indicator, right
+    /// below the matching dora indicator.
+    pub fn ura_dora_indicator_position(n: usize) -> usize {
+        Wall::dora_indicator_position(n) + 1
+    }
+
+    pub fn dora_indicators(&self) -> Vec<Tile> {
+        (0..=self.kan_doras)
+            .map(|n| self.dead[Wall::dora_indicator_position(n)])
+            .collect()
+    }
+
+    pub fn ura_dora_indicators(&self) -> Vec<Tile> {
+        (0..=self.kan_doras)
+            .map(|n| self.dead[Wall::ura_dora_indicator_position(n)])
+            .collect()
+    }
+
+    pub fn doras(&self, ruleset: &Ruleset) -> Vec<Tile> {
+        self.dora_indicators()
+            .into_iter()
+            .map(|indicator| ruleset.indicate_dora(indicator))
+            .collect()
+    }
+
+    /// tiles left to draw in the live wall.
+    pub fn remaining(&self) -> usize {
+        self.live.len()
+    }
+
+    /// whether the last tile of the live wall has been drawn, which makes
+    /// the next win "under the sea" or "under the river".
+    pub fn is_haitei(&self) -> bool {
+        self.live.is_empty()
+    }
+
+    pub fn replacements_drawn(&self) -> usize {
+        self.replacements
+    }
+}
+
+/// the wall as it was built, the dice followed by every tile in stacking
+/// order, e.g. `3 4 M1 GD ...`.
+impl Display for Wall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.dice.0, self.dice.1)?;
+        for tile in &self.tiles {
+            write!(f, " {}", tile)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseWallError(pub String);
+
+impl Display for ParseWallError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid wall: {}", self.0)
+    }
+}
+
+impl FromStr for Wall {
+    type Err = ParseWallError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut tokens = s.split_whitespace();
+        let mut die = || match tokens.next().map(str::parse::<u8>) {
+            Some(Ok(value @ 1..=6)) => Ok(value),
+            _ => Err(ParseWallError("expected two dice".to_string())),
+        };
+        let dice = (die()?, die()?);
+
+        let tiles = tokens
+            .map(|token| {
+                token
+                    .parse::<Tile>()
+                    .map_err(|error| ParseWallError(error.to_string()))
+            })
+            .collect::<Result<Vec<Tile>, ParseWallError>>()?;
+        // a full set also leaves room for the dead wall and the deal.
+        let mut sorted = tiles.clone();
+        sorted.sort();
+        let is_set = [Ruleset::riichi(), Ruleset::sanma(), Ruleset::chinese()]
+            .iter()
+            .any(|ruleset| {
+                let mut set = ruleset.tiles();
+                set.sort();
+                set == sorted
+            });
+        if !is_set {
+            return Err(ParseWallError(format!(
+                "{} tiles aren't the set of any ruleset",
+                tiles.len()
+            )));
+        }
+
+        Ok(Wall::new(tiles, dice))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        rng::Rng,
+        ruleset::Ruleset,
+        tile::{Simple, Tile},
+        wall::{salt, Wall},
+    };
+
+    #[test]
+    fn break_from_dice() {
+        let tiles = Ruleset::riichi().tiles();
+
+        // 7 is across the table: the break is 7 stacks into the third side
+        // clockwise, so the live wall starts at stack 2 * 17 + 7 = 41 and the
+        // first dora indicator sits on top of stack 41 - 3.
+        let mut wall = Wall::new(tiles.clone(), (3, 4));
+        assert_eq!(wall.remaining(), 122);
+        assert_eq!(wall.draw(), Some(tiles[2 * 41]));
+        assert_eq!(wall.draw(), Some(tiles[2 * 41 + 1]));
+        assert_eq!(wall.dora_indicators(), vec![tiles[2 * 38]]);
+        assert_eq!(wall.ura_dora_indicators(), vec![tiles[2 * 38 + 1]]);
+
+        // 5 is the dealer's own side, so the dead wall wraps around the
+        // start of the stacks.
+        let mut wall = Wall::new(tiles.clone(), (1, 4));
+        assert_eq!(wall.draw(), Some(tiles[2 * 5]));
+        assert_eq!(wall.dora_indicators(), vec![tiles[2 * 2]]);
+
+        // 4 is the dealer's left, the second side clockwise.
+        let wall = Wall::new(tiles.clone(), (2, 2));
+        assert_eq!(wall.live[0], tiles[2 * 21]);
+
+        // with the unshuffled set, stack 53 holds the last two 9 sou and the
+        // indicator on stack 50 is a 9 pin.
+        let wall = Wall::new(tiles, (1, 1));
+        assert_eq!(wall.live[0], Tile::Simple(Simple::Sou(9)));
+        assert_eq!(wall.dora_indicators(), vec![Tile::Simple(Simple::Pin(9))]);
+        assert_eq!(
+            wall.doras(&Ruleset::riichi()),
+            vec![Tile::Simple(Simple::Pin(1))]
+        );
+
+        // a sanma wall has three sides of 18 stacks, 5 being the dealer's
+        // right, the third side clockwise.
+        let sanma = Ruleset::sanma();
+        let tiles = sanma.tiles();
+        let wall = Wall::new(tiles.clone(), (2, 3));
+        assert_eq!(wall.remaining(), 94);
+        assert_eq!(wall.live[0], tiles[2 * (2 * 18 + 5)]);
+    }
+
+    #[test]
+    fn replacement_draws() {
+        let tiles = Ruleset::riichi().tiles();
+        let mut wall = Wall::new(tiles.clone(), (1, 1));
+
+        // the live wall ends on the stack right before the dead wall.
+        let last = *wall.live.back().unwrap();
+        assert_eq!(last, tiles[2 * 45 + 1]);
+
+        let indicator = wall.dora_indicators()[0];
+        assert_eq!(wall.draw_replacement(), Some(tiles[2 * 52]));
+        assert!(wall.reveal_kan_dora());
+        for _ in 0..3 {
+            assert!(wall.draw_replacement().is_some());
+            assert!(wall.reveal_kan_dora());
+        }
+        assert!(!wall.reveal_kan_dora());
+        assert_eq!(wall.remaining(), 118);
+        assert_eq!(wall.dora_indicators().len(), 5);
+        assert_eq!(wall.dora_indicators()[0], indicator);
+
+        // past the four rinshan tiles the moved live tiles come back out.
+        assert_eq!(wall.draw_replacement(), Some(last));
+        assert_eq!(wall.replacements_drawn(), 5);
+
+        while wall.draw().is_some() {}
+        assert!(wall.is_haitei());
+        assert_eq!(wall.draw_replacement(), None);
+    }
+
+    #[test]
+    fn export_and_verify() {
+        let wall = Wall::shuffled(&Ruleset::riichi(), &mut Rng::new(2024));
+        let same = Wall::shuffled(&Ruleset::riichi(), &mut Rng::new(2024));
+        let other = Wall::shuffled(&Ruleset::riichi(), &mut Rng::new(2025));
+        let salt = salt();
+        assert_eq!(wall.to_string(), same.to_string());
+        assert_eq!(wall.hash(&salt), same.hash(&salt));
+        assert_ne!(wall.hash(&salt), other.hash(&salt));
+        assert_ne!(wall.hash(&salt), wall.hash(""));
+        assert_ne!(salt, super::salt());
+
+        let exported = wall.to_string();
+        let mut imported: Wall = exported.parse().unwrap();
+        assert_eq!(imported.to_string(), exported);
+        assert_eq!(imported.hash(&salt), wall.hash(&salt));
+        assert_eq!(imported.dice(), wall.dice());
+        assert_eq!(imported.draw(), wall.clone().draw());
+
+        assert!("3 4".parse::<Wall>().is_err());
+        assert!("0 4 M1 M1 M1 M1 M2 M2 M2 M2".parse::<Wall>().is_err());
+        assert!("1 4 M1 M1 M1 M1 M2 M2 M2 XX".parse::<Wall>().is_err());
+        assert!("1 4 M1 M1 M1 M1".parse::<Wall>().is_err());
+        // equal sides, but too few tiles for a dead wall and a deal.
+        assert!("1 4 M1 M1 M1 M1 M2 M2 M2 M2".parse::<Wall>().is_err());
+        // a full count, but a fifth M1 instead of a M2.
+        let forged = exported.replacen("M2", "M1", 1);
+        assert!(forged.parse::<Wall>().is_err());
+
+        let sanma = Wall::shuffled(&Ruleset::sanma(), &mut Rng::new(3));
+        let imported: Wall = sanma.to_string().parse().unwrap();
+        assert_eq!(imported.hash(&salt), sanma.hash(&salt));
+        let chinese = Wall::shuffled(&Ruleset::chinese(), &mut Rng::new(3));
+        assert!(chinese.to_string().parse::<Wall>().is_ok());
+    }
+}
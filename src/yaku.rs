@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use crate::tile::{Dragon, Wind};
 
-#[derive(PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
 pub enum Yaku {
     // 1-han
     Riichi,
@@ -48,6 +48,9 @@ pub enum Yaku {
 
     // mangan
     ManganAtDraw,
+    /// every discard a terminal or honor and none of them called when the
+    /// wall runs out.
+    NagashiMangan,
     Iipinmoyue,
     Chuupinraoyui,
 
@@ -75,6 +78,171 @@ pub enum Yaku {
     TrueNineGates,
     FourBigWinds,
     BigSevenStars,
+
+    // bonus tiles
+    SeatFlower,
+    SeatSeason,
+    AllFlowers,
+    AllSeasons,
+}
+
+impl Yaku {
+    /// the han the yaku is worth, 13 per yakuman. closed-only yakus are
+    /// worth nothing in an open hand and some lose a han when open
+    /// (kuisagari).
+    pub fn han(self, menzenchin: bool) -> u8 {
+        let (closed, open) = match self {
+            Yaku::Riichi
+            | Yaku::FullyConcealedHand
+            | Yaku::Pinfu
+            | Yaku::PureDoubleSequence
+            | Yaku::Ippatsu => (1, 0),
+            Yaku::AllSimples
+            | Yaku::SeatWind(_)
+            | Yaku::PrevalentWind(_)
+            | Yaku::Dragons(_)
+            | Yaku::RobbingAKan
+            | Yaku::AfterAKan
+            | Yaku::UnderTheSea
+            | Yaku::UnderTheRiver
+            | Yaku::TsubameGaeshi
+            | Yaku::Kanburi
+            | Yaku::Shiiatutaotai => (1, 1),
+
+            Yaku::DoubeRiichi | Yaku::SevenPairs => (2, 0),
+            Yaku::HalfOutsideHand
+            | Yaku::PureStraight
+            | Yaku::MixedTripleSequence => (2, 1),
+            Yaku::TripleTriplets
+            | Yaku::ThreeQuads
+            | Yaku::AllTriplets
+            | Yaku::ThreeConcealedTriplets
+            | Yaku::LittleThreeDragons
+            | Yaku::AllTerminalsAndHonors
+            | Yaku::Uumensai
+            | Yaku::ThreeChainedTriplets => (2, 2),
+
+            Yaku::TwicePureDoubleSequence => (3, 0),
+            Yaku::FullyOutsideHand | Yaku::HalfFlush | Yaku::PureTripleChow => {
+                (3, 2)
+            }
+
+            Yaku::FullFlush => (6, 5),
+
+            Yaku::ManganAtDraw
+            | Yaku::NagashiMangan
+            | Yaku::Iipinmoyue
+            | Yaku::Chuupinraoyui => (5, 5),
+
+            Yaku::BlessingOfHeaven
+            | Yaku::BlessingOfEarth
+            | Yaku::FourConcealedTriplets
+            | Yaku::ThirteenOrphans
+            | Yaku::NineGates
+            | Yaku::HandOfMan
+            | Yaku::BigWheels
+            | Yaku::BambooForest
+            | Yaku::NumerousNeighbours => (13, 0),
+            Yaku::BigThreeDragons
+            | Yaku::AllHonors
+            | Yaku::AllGreen
+            | Yaku::AllTerminals
+            | Yaku::FourLittleWinds
+            | Yaku::FourQuads
+            | Yaku::Ishinouenimosannen => (13, 13),
+
+            Yaku::SingleWaitFourConcealedTriplets
+            | Yaku::ThirteenWaitThirteenOrphans
+            | Yaku::TrueNineGates
+            | Yaku::BigSevenStars => (26, 0),
+            Yaku::FourBigWinds => (26, 26),
+
+            Yaku::SeatFlower
+            | Yaku::SeatSeason
+            | Yaku::AllFlowers
+            | Yaku::AllSeasons => (1, 1),
+        };
+        if menzenchin {
+            closed
+        } else {
+            open
+        }
+    }
+
+    /// every yaku, including one entry per wind or dragon for the
+    /// parameterised ones.
+    pub fn all() -> Vec<Yaku> {
+        let mut yakus = vec![
+            Yaku::Riichi,
+            Yaku::AllSimples,
+            Yaku::FullyConcealedHand,
+            Yaku::Pinfu,
+            Yaku::PureDoubleSequence,
+            Yaku::RobbingAKan,
+            Yaku::AfterAKan,
+            Yaku::UnderTheSea,
+            Yaku::UnderTheRiver,
+            Yaku::Ippatsu,
+            Yaku::TsubameGaeshi,
+            Yaku::Kanburi,
+            Yaku::Shiiatutaotai,
+            Yaku::DoubeRiichi,
+            Yaku::TripleTriplets,
+            Yaku::ThreeQuads,
+            Yaku::AllTriplets,
+            Yaku::ThreeConcealedTriplets,
+            Yaku::LittleThreeDragons,
+            Yaku::AllTerminalsAndHonors,
+            Yaku::SevenPairs,
+            Yaku::HalfOutsideHand,
+            Yaku::PureStraight,
+            Yaku::MixedTripleSequence,
+            Yaku::Uumensai,
+            Yaku::ThreeChainedTriplets,
+            Yaku::TwicePureDoubleSequence,
+            Yaku::FullyOutsideHand,
+            Yaku::HalfFlush,
+            Yaku::PureTripleChow,
+            Yaku::FullFlush,
+            Yaku::ManganAtDraw,
+            Yaku::NagashiMangan,
+            Yaku::Iipinmoyue,
+            Yaku::Chuupinraoyui,
+            Yaku::BlessingOfHeaven,
+            Yaku::BlessingOfEarth,
+            Yaku::BigThreeDragons,
+            Yaku::FourConcealedTriplets,
+            Yaku::AllHonors,
+            Yaku::AllGreen,
+            Yaku::AllTerminals,
+            Yaku::ThirteenOrphans,
+            Yaku::FourLittleWinds,
+            Yaku::FourQuads,
+            Yaku::NineGates,
+            Yaku::HandOfMan,
+            Yaku::BigWheels,
+            Yaku::BambooForest,
+            Yaku::NumerousNeighbours,
+            Yaku::Ishinouenimosannen,
+            Yaku::SingleWaitFourConcealedTriplets,
+            Yaku::ThirteenWaitThirteenOrphans,
+            Yaku::TrueNineGates,
+            Yaku::FourBigWinds,
+            Yaku::BigSevenStars,
+            Yaku::SeatFlower,
+            Yaku::SeatSeason,
+            Yaku::AllFlowers,
+            Yaku::AllSeasons,
+        ];
+        for wind in [Wind::East, Wind::South, Wind::West, Wind::North] {
+            yakus.push(Yaku::SeatWind(wind));
+            yakus.push(Yaku::PrevalentWind(wind));
+        }
+        for dragon in [Dragon::Green, Dragon::Red, Dragon::White] {
+            yakus.push(Yaku::Dragons(dragon));
+        }
+        yakus
+    }
 }
 
 impl Display for Yaku {
@@ -147,6 +315,7 @@ impl Display for Yaku {
             Yaku::PureTripleChow => write!(f, "Pure triple Chow"),
             Yaku::FullFlush => write!(f, "Full flush"),
             Yaku::ManganAtDraw => write!(f, "Mangan at draw"),
+            Yaku::NagashiMangan => write!(f, "Nagashi mangan"),
             Yaku::Iipinmoyue => write!(f, "Iipinmoyue"),
             Yaku::Chuupinraoyui => write!(f, "Chuupinraoyui"),
             Yaku::BlessingOfHeaven => write!(f, "Blessing of heaven"),
@@ -174,6 +343,10 @@ impl Display for Yaku {
             Yaku::TrueNineGates => write!(f, "True nine gates"),
             Yaku::FourBigWinds => write!(f, "Four big winds"),
             Yaku::BigSevenStars => write!(f, "Big seven stars"),
+            Yaku::SeatFlower => write!(f, "Seat flower"),
+            Yaku::SeatSeason => write!(f, "Seat season"),
+            Yaku::AllFlowers => write!(f, "All flowers"),
+            Yaku::AllSeasons => write!(f, "All seasons"),
         }
     }
 }